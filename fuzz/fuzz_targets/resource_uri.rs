@@ -0,0 +1,46 @@
+//! Arbitrary `lines=`/`bytes=` queries on the fixture's source resources.
+//!
+//! The first byte picks a file and the rest, as UTF-8, is appended to its
+//! URI as the query, so the fuzzer spends its time in range parsing and
+//! slicing rather than on URIs that don't resolve.
+
+#![no_main]
+
+use amari_mcp::config::LibraryManifest;
+use amari_mcp::resources::SourceResources;
+use amari_mcp::tools::SharedState;
+use libfuzzer_sys::fuzz_target;
+use std::path::Path;
+use std::sync::OnceLock;
+
+fn fixture() -> &'static (SourceResources, Vec<String>) {
+    static FIXTURE: OnceLock<(SourceResources, Vec<String>)> = OnceLock::new();
+    FIXTURE.get_or_init(|| {
+        let manifest_path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/minilib.toml");
+        let manifest = LibraryManifest::load(&manifest_path).expect("fixture manifest");
+        let index = amari_mcp::parser::build_index(&manifest, &manifest_path)
+            .expect("fixture index")
+            .validate()
+            .expect("fixture index validates");
+        let resources = SourceResources::new(&SharedState::new(index, manifest));
+        let uris = resources.files().into_iter().map(|(uri, _)| uri).collect();
+        (resources, uris)
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, query)) = data.split_first() else {
+        return;
+    };
+    let Ok(query) = std::str::from_utf8(query) else {
+        return;
+    };
+    let (resources, uris) = fixture();
+    let uri = &uris[usize::from(selector) % uris.len()];
+    if let Ok(file) = resources.read(&format!("{uri}?{query}")) {
+        // The returned URI must itself be readable and select the same text.
+        let again = resources.read(&file.uri).expect("returned URI is readable");
+        assert_eq!(again.text, file.text);
+    }
+});
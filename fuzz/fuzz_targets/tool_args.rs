@@ -0,0 +1,71 @@
+//! Arbitrary arguments for every reference tool over the test fixture.
+//!
+//! The first byte picks the tool and the rest is parsed as its JSON
+//! arguments. Each call goes through the registry, so coercion, schema
+//! validation and the handler's own parsing all see the input. libFuzzer's
+//! panic hook aborts even inside the registry's panic isolation, so any
+//! handler panic is reported as a crash.
+
+#![no_main]
+
+use amari_mcp::config::LibraryManifest;
+use amari_mcp::tools::{registry::ToolRegistry, SharedState};
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Tools whose arguments name other checkouts or project directories, which
+/// would have the fuzzer parsing arbitrary parts of the filesystem.
+const SKIPPED: &[&str] = &["api_diff", "upgrade_advice"];
+
+struct Fixture {
+    runtime: tokio::runtime::Runtime,
+    tools: ToolRegistry,
+    names: Vec<String>,
+}
+
+fn fixture() -> &'static Fixture {
+    static FIXTURE: OnceLock<Fixture> = OnceLock::new();
+    FIXTURE.get_or_init(|| {
+        let manifest_path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/minilib.toml");
+        let manifest = LibraryManifest::load(&manifest_path).expect("fixture manifest");
+        let index = amari_mcp::parser::build_index(&manifest, &manifest_path)
+            .expect("fixture index")
+            .validate()
+            .expect("fixture index validates");
+        let tools = amari_mcp::tools::reference_tools(&SharedState::new(index, manifest));
+        let names = tools
+            .names()
+            .into_iter()
+            .filter(|name| !SKIPPED.contains(name))
+            .map(str::to_string)
+            .collect();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("tokio runtime");
+        Fixture {
+            runtime,
+            tools,
+            names,
+        }
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, json)) = data.split_first() else {
+        return;
+    };
+    let Ok(args) = serde_json::from_slice::<Value>(json) else {
+        return;
+    };
+    let fixture = fixture();
+    let name = &fixture.names[usize::from(selector) % fixture.names.len()];
+
+    // Errors are expected for most inputs; only panics matter.
+    let _ = fixture
+        .runtime
+        .block_on(fixture.tools.call_local(name, args));
+});
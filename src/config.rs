@@ -4,16 +4,30 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 /// Top-level library manifest loaded from a TOML file.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct LibraryManifest {
     pub library: LibraryInfo,
     pub workspace: WorkspaceInfo,
     pub crates: CrateGroups,
     pub aliases: HashMap<String, String>,
+    #[serde(default)]
+    pub server: ServerConfig,
+    /// Older checkouts of the library that `api_diff` can compare against,
+    /// keyed by label (e.g. a version), with paths relative to the manifest.
+    #[serde(default)]
+    pub baselines: HashMap<String, String>,
+    /// Other libraries the tools can answer about through their `workspace`
+    /// argument (a fork, a user's own crates), keyed by name, each the path
+    /// of its own manifest relative to this one.
+    #[serde(default)]
+    pub workspaces: HashMap<String, String>,
+    /// Path the manifest was loaded from; empty when parsed from a string.
+    #[serde(skip)]
+    pub manifest_path: PathBuf,
 }
 
 /// Metadata about the target library.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct LibraryInfo {
     pub name: String,
     pub display_name: String,
@@ -25,14 +39,18 @@ pub struct LibraryInfo {
 }
 
 /// Workspace layout information.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct WorkspaceInfo {
     pub root_cargo_toml: String,
     pub umbrella_crate: String,
+    /// Extra directories (relative to the manifest) that module files may be
+    /// read from in addition to the source root.
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
 }
 
 /// Categorized workspace crate groups.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct CrateGroups {
     pub default: CrateList,
     pub optional: HashMap<String, String>,
@@ -40,11 +58,193 @@ pub struct CrateGroups {
 }
 
 /// A list of crate directory names.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct CrateList {
     pub members: Vec<String>,
 }
 
+/// Runtime settings for the MCP server (`[server]` table).
+///
+/// The table is optional; omitted fields fall back to their defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Maximum tool calls executing at once across all tools.
+    pub max_concurrent_calls: usize,
+    /// Per-tool concurrency caps, keyed by tool name.
+    pub tool_concurrency: HashMap<String, usize>,
+    /// Token-bucket rate limit on incoming tool calls. Unlimited when omitted.
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Bounds on tool arguments.
+    pub limits: ResourceLimits,
+    /// Named set of tools to register.
+    pub profile: ToolProfile,
+    /// Tools registered in addition to the profile.
+    pub enable_tools: Vec<String>,
+    /// Tools removed from the profile.
+    pub disable_tools: Vec<String>,
+    /// Worker threads used to parse crates in parallel. Defaults to one per CPU.
+    pub threads: Option<usize>,
+    /// Log a summary of tool usage this often, in seconds. Off when omitted.
+    pub stats_log_interval_secs: Option<u64>,
+    /// How long shutdown waits for running tool calls before cancelling them.
+    pub drain_timeout_secs: u64,
+    /// Check the indexed source files for changes this often, in seconds.
+    /// Off when omitted.
+    pub watch_interval_secs: Option<u64>,
+    /// Compiled-in tool plugins to install, keyed by plugin name.
+    pub plugins: HashMap<String, PluginConfig>,
+    /// Directories, relative to the manifest, whose projects `upgrade_advice`
+    /// may scan. The tool is refused everywhere when empty.
+    pub project_roots: Vec<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_calls: 8,
+            tool_concurrency: HashMap::new(),
+            rate_limit: None,
+            limits: ResourceLimits::default(),
+            profile: ToolProfile::default(),
+            enable_tools: Vec::new(),
+            disable_tools: Vec::new(),
+            threads: None,
+            stats_log_interval_secs: None,
+            drain_timeout_secs: 10,
+            watch_interval_secs: None,
+            plugins: HashMap::new(),
+            project_roots: Vec::new(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Settings that parse but can't work, such as zero-sized limits.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.max_concurrent_calls == 0 {
+            problems.push("server.max_concurrent_calls must be at least 1".to_string());
+        }
+        for (tool, cap) in &self.tool_concurrency {
+            if *cap == 0 {
+                problems.push(format!("server.tool_concurrency.{tool} must be at least 1"));
+            }
+        }
+        if let Some(rate) = &self.rate_limit {
+            if rate.calls_per_second.is_nan() || rate.calls_per_second <= 0.0 {
+                problems.push("server.rate_limit.calls_per_second must be positive".to_string());
+            }
+            if rate.burst == 0 {
+                problems.push("server.rate_limit.burst must be at least 1".to_string());
+            }
+        }
+        for (field, value) in [
+            ("max_results", self.limits.max_results),
+            ("max_query_length", self.limits.max_query_length),
+            ("max_batch_calls", self.limits.max_batch_calls),
+            ("max_response_bytes", self.limits.max_response_bytes),
+        ] {
+            if value == 0 {
+                problems.push(format!("server.limits.{field} must be at least 1"));
+            }
+        }
+        if self.threads == Some(0) {
+            problems.push("server.threads must be at least 1".to_string());
+        }
+        if self.stats_log_interval_secs == Some(0) {
+            problems.push("server.stats_log_interval_secs must be at least 1".to_string());
+        }
+        if self.watch_interval_secs == Some(0) {
+            problems.push("server.watch_interval_secs must be at least 1".to_string());
+        }
+        for (name, plugin) in &self.plugins {
+            let prefix = plugin.prefix(name);
+            if !is_valid_prefix(prefix) {
+                problems.push(format!(
+                    "server.plugins.{name}: prefix '{prefix}' must be letters, digits, or '-'"
+                ));
+            }
+        }
+        problems
+    }
+}
+
+/// One `[server.plugins.<name>]` entry.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PluginConfig {
+    /// Namespace for the plugin's tools, which are exposed as
+    /// `<prefix>_<tool>`. Defaults to the plugin name.
+    pub prefix: Option<String>,
+    /// Plugin-specific settings, passed through unparsed.
+    pub settings: toml::Table,
+}
+
+impl PluginConfig {
+    /// The configured prefix, or `name` when none is set.
+    pub fn prefix<'a>(&'a self, name: &'a str) -> &'a str {
+        self.prefix.as_deref().unwrap_or(name)
+    }
+}
+
+/// Whether `prefix` can namespace tool names.
+pub fn is_valid_prefix(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Predefined tool sets selectable with `[server] profile = "..."`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToolProfile {
+    /// Name lookup only: `api_search`, `type_info` and `server_info`.
+    Minimal,
+    /// All single-purpose reference tools, plus `capability_matrix` and
+    /// `server_info`.
+    Reference,
+    /// Every tool, including composite ones such as `batch_call`.
+    #[default]
+    Full,
+}
+
+/// Upper bounds on tool arguments (`[server.limits]`), so a single request
+/// can't ask for an unbounded amount of work or output.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Largest accepted `limit` argument on listing tools.
+    pub max_results: usize,
+    /// Longest accepted query or name string, in characters.
+    pub max_query_length: usize,
+    /// Most calls accepted in one `batch_call`.
+    pub max_batch_calls: usize,
+    /// Largest tool response, in bytes of serialized JSON.
+    pub max_response_bytes: usize,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_results: 200,
+            max_query_length: 256,
+            max_batch_calls: 100,
+            max_response_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// Token-bucket parameters for rate limiting tool calls.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitConfig {
+    /// Sustained calls per second.
+    pub calls_per_second: f64,
+    /// Calls allowed in a burst above the sustained rate.
+    pub burst: u32,
+}
+
 /// A resolved crate entry with its feature gate and alias.
 #[derive(Debug, Clone)]
 pub struct ResolvedCrate {
@@ -58,8 +258,9 @@ impl LibraryManifest {
     pub fn load(path: &Path) -> Result<Self> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read manifest at {}", path.display()))?;
-        let manifest: Self = toml::from_str(&content)
+        let mut manifest: Self = toml::from_str(&content)
             .with_context(|| format!("Failed to parse manifest at {}", path.display()))?;
+        manifest.manifest_path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
         Ok(manifest)
     }
 
@@ -69,6 +270,44 @@ impl LibraryManifest {
         manifest_dir.join(&self.library.source_path)
     }
 
+    /// Resolve `workspace.allowed_paths` relative to the manifest file's directory.
+    pub fn resolve_allowed_paths(&self) -> Vec<PathBuf> {
+        let manifest_dir = self
+            .manifest_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."));
+        self.workspace
+            .allowed_paths
+            .iter()
+            .map(|path| manifest_dir.join(path))
+            .collect()
+    }
+
+    /// Resolve `server.project_roots` relative to the manifest file's directory.
+    pub fn resolve_project_roots(&self) -> Vec<PathBuf> {
+        let manifest_dir = self
+            .manifest_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."));
+        self.server
+            .project_roots
+            .iter()
+            .map(|path| manifest_dir.join(path))
+            .collect()
+    }
+
+    /// Resolve `[workspaces]` manifest paths relative to the manifest file's
+    /// directory.
+    pub fn resolve_workspace(&self, name: &str) -> Option<PathBuf> {
+        let manifest_dir = self
+            .manifest_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."));
+        self.workspaces
+            .get(name)
+            .map(|path| manifest_dir.join(path))
+    }
+
     /// Get all user-facing crates (default + optional), with aliases and feature gates.
     pub fn all_user_facing_crates(&self) -> Vec<ResolvedCrate> {
         let mut crates: Vec<ResolvedCrate> = self
@@ -164,6 +403,7 @@ testlib-extra = "extra"
         let manifest = parse_sample();
         assert_eq!(manifest.workspace.root_cargo_toml, "Cargo.toml");
         assert_eq!(manifest.workspace.umbrella_crate, "src/lib.rs");
+        assert!(manifest.workspace.allowed_paths.is_empty());
     }
 
     #[test]
@@ -273,6 +513,118 @@ members = []
         assert!(manifest.crates.optional.is_empty());
     }
 
+    #[test]
+    fn server_section_defaults_when_omitted() {
+        let manifest = parse_sample();
+        assert_eq!(manifest.server.max_concurrent_calls, 8);
+        assert!(manifest.server.tool_concurrency.is_empty());
+        assert!(manifest.server.rate_limit.is_none());
+        assert_eq!(manifest.server.limits.max_results, 200);
+        assert_eq!(manifest.server.profile, ToolProfile::Full);
+        assert!(manifest.server.threads.is_none());
+        assert_eq!(manifest.server.drain_timeout_secs, 10);
+        assert!(manifest.server.watch_interval_secs.is_none());
+    }
+
+    #[test]
+    fn parses_tool_profile_and_overrides() {
+        let toml_str = format!(
+            "{}\n[server]\nprofile = \"minimal\"\nenable_tools = [\"browse_docs\"]\ndisable_tools = [\"type_info\"]\n",
+            sample_toml()
+        );
+        let manifest: LibraryManifest = toml::from_str(&toml_str).unwrap();
+        assert_eq!(manifest.server.profile, ToolProfile::Minimal);
+        assert_eq!(manifest.server.enable_tools, vec!["browse_docs"]);
+        assert_eq!(manifest.server.disable_tools, vec!["type_info"]);
+    }
+
+    #[test]
+    fn partial_limits_keep_remaining_defaults() {
+        let toml_str = format!("{}\n[server.limits]\nmax_results = 50\n", sample_toml());
+        let manifest: LibraryManifest = toml::from_str(&toml_str).unwrap();
+        assert_eq!(manifest.server.limits.max_results, 50);
+        assert_eq!(manifest.server.limits.max_query_length, 256);
+        assert_eq!(manifest.server.limits.max_batch_calls, 100);
+    }
+
+    #[test]
+    fn parses_server_limits() {
+        let toml_str = format!(
+            "{}\n[server]\nmax_concurrent_calls = 2\n\n[server.tool_concurrency]\nbatch_call = 1\n\n[server.rate_limit]\ncalls_per_second = 5.0\nburst = 10\n",
+            sample_toml()
+        );
+        let manifest: LibraryManifest = toml::from_str(&toml_str).unwrap();
+        assert_eq!(manifest.server.max_concurrent_calls, 2);
+        assert_eq!(manifest.server.tool_concurrency.get("batch_call"), Some(&1));
+        let rate_limit = manifest.server.rate_limit.unwrap();
+        assert_eq!(rate_limit.calls_per_second, 5.0);
+        assert_eq!(rate_limit.burst, 10);
+    }
+
+    #[test]
+    fn default_server_config_has_no_problems() {
+        assert!(ServerConfig::default().problems().is_empty());
+    }
+
+    #[test]
+    fn reports_unusable_server_settings() {
+        let toml_str = format!(
+            "{}\n[server]\nmax_concurrent_calls = 0\nthreads = 0\nwatch_interval_secs = 0\n\n[server.rate_limit]\ncalls_per_second = 0.0\nburst = 5\n\n[server.limits]\nmax_results = 0\n",
+            sample_toml()
+        );
+        let manifest: LibraryManifest = toml::from_str(&toml_str).unwrap();
+        let problems = manifest.server.problems();
+        assert_eq!(problems.len(), 5);
+        assert!(problems.iter().any(|p| p.contains("max_concurrent_calls")));
+        assert!(problems.iter().any(|p| p.contains("calls_per_second")));
+        assert!(problems.iter().any(|p| p.contains("limits.max_results")));
+        assert!(problems.iter().any(|p| p.contains("threads")));
+        assert!(problems.iter().any(|p| p.contains("watch_interval_secs")));
+    }
+
+    #[test]
+    fn parses_plugin_tables() {
+        let toml_str = format!(
+            "{}\n[server.plugins.geometry]\nprefix = \"geo\"\n\n[server.plugins.geometry.settings]\nprecision = 6\n\n[server.plugins.bad]\nprefix = \"a b\"\n",
+            sample_toml()
+        );
+        let manifest: LibraryManifest = toml::from_str(&toml_str).unwrap();
+        let geometry = &manifest.server.plugins["geometry"];
+        assert_eq!(geometry.prefix("geometry"), "geo");
+        assert_eq!(geometry.settings["precision"].as_integer(), Some(6));
+
+        let problems = manifest.server.problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("server.plugins.bad"));
+    }
+
+    #[test]
+    fn parses_baselines() {
+        let toml_str = format!(
+            "{}\n[baselines]\n\"0.17\" = \"../testlib-0.17\"\n",
+            sample_toml()
+        );
+        let manifest: LibraryManifest = toml::from_str(&toml_str).unwrap();
+        assert_eq!(manifest.baselines.get("0.17").unwrap(), "../testlib-0.17");
+        assert!(parse_sample().baselines.is_empty());
+    }
+
+    #[test]
+    fn parses_workspaces() {
+        let toml_str = format!(
+            "{}\n[workspaces]\nfork = \"../fork/manifest.toml\"\n",
+            sample_toml()
+        );
+        let mut manifest: LibraryManifest = toml::from_str(&toml_str).unwrap();
+        manifest.manifest_path = PathBuf::from("/etc/amari/amari.toml");
+        assert_eq!(
+            manifest.resolve_workspace("fork").unwrap(),
+            Path::new("/etc/amari/../fork/manifest.toml")
+        );
+        assert!(manifest.resolve_workspace("other").is_none());
+        assert!(parse_sample().workspaces.is_empty());
+    }
+
     #[test]
     fn load_from_file_works() {
         // Test loading the actual amari manifest
@@ -280,6 +632,7 @@ members = []
         if manifest_path.exists() {
             let manifest = LibraryManifest::load(&manifest_path).unwrap();
             assert_eq!(manifest.library.name, "amari");
+            assert!(manifest.manifest_path.is_absolute());
             assert_eq!(manifest.crates.default.members.len(), 9);
             assert_eq!(manifest.crates.optional.len(), 10);
             assert_eq!(manifest.aliases.len(), 19);
@@ -0,0 +1,154 @@
+//! Golden-output comparison for the conformance tests in `tests/`.
+//!
+//! Tool responses are normalized before comparison so that golden files are
+//! stable across machines and runs: absolute paths are rewritten relative to
+//! a fixture root and volatile fields are redacted. Arrays keep their order,
+//! so rankings and "in input order" results are checked too; tools that
+//! build listings from hash maps sort them before responding.
+
+use serde_json::Value;
+use std::path::Path;
+
+/// Absolute tolerance for comparing floating-point numbers.
+pub const FLOAT_TOLERANCE: f64 = 1e-9;
+
+/// Fields whose values change between runs or releases, as JSON pointers.
+pub const REDACTED: &[&str] = &["/server/uptime_secs", "/server/version"];
+
+/// Timing fields, redacted wherever they appear.
+pub const REDACTED_KEYS: &[&str] = &["mean_ms", "max_ms"];
+
+/// Placeholder substituted for the fixture root in string values.
+pub const ROOT_PLACEHOLDER: &str = "<root>";
+
+/// Rewrite a tool response into its stable, comparable form.
+pub fn normalize(response: &mut Value, root: &Path) {
+    for pointer in REDACTED {
+        if let Some(value) = response.pointer_mut(pointer) {
+            *value = Value::String("<redacted>".to_string());
+        }
+    }
+    canonicalize(response, &root.display().to_string());
+}
+
+fn canonicalize(value: &mut Value, root: &str) {
+    match value {
+        Value::String(s) if !root.is_empty() && s.contains(root) => {
+            *s = s.replace(root, ROOT_PLACEHOLDER);
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                canonicalize(item, root);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                if REDACTED_KEYS.contains(&key.as_str()) {
+                    *item = Value::String("<redacted>".to_string());
+                } else {
+                    canonicalize(item, root);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Differences between an expected and an actual value, one message per
+/// mismatch, each prefixed with the JSON pointer where it occurs. Numbers
+/// are equal when they differ by at most `tolerance`.
+pub fn compare(expected: &Value, actual: &Value, tolerance: f64) -> Vec<String> {
+    let mut diffs = Vec::new();
+    compare_at("", expected, actual, tolerance, &mut diffs);
+    diffs
+}
+
+fn compare_at(
+    pointer: &str,
+    expected: &Value,
+    actual: &Value,
+    tolerance: f64,
+    diffs: &mut Vec<String>,
+) {
+    match (expected, actual) {
+        (Value::Number(e), Value::Number(a)) => {
+            let (e, a) = (
+                e.as_f64().unwrap_or(f64::NAN),
+                a.as_f64().unwrap_or(f64::NAN),
+            );
+            if (e - a).abs() > tolerance || e.is_nan() != a.is_nan() {
+                diffs.push(format!("{pointer}: expected {e}, got {a}"));
+            }
+        }
+        (Value::Array(e), Value::Array(a)) => {
+            if e.len() != a.len() {
+                diffs.push(format!(
+                    "{pointer}: expected {} entries, got {}",
+                    e.len(),
+                    a.len()
+                ));
+                return;
+            }
+            for (index, (e, a)) in e.iter().zip(a).enumerate() {
+                compare_at(&format!("{pointer}/{index}"), e, a, tolerance, diffs);
+            }
+        }
+        (Value::Object(e), Value::Object(a)) => {
+            for (key, e_value) in e {
+                let child = format!("{pointer}/{key}");
+                match a.get(key) {
+                    Some(a_value) => compare_at(&child, e_value, a_value, tolerance, diffs),
+                    None => diffs.push(format!("{child}: missing")),
+                }
+            }
+            for key in a.keys().filter(|key| !e.contains_key(*key)) {
+                diffs.push(format!("{pointer}/{key}: unexpected field"));
+            }
+        }
+        (e, a) if e == a => {}
+        (e, a) => diffs.push(format!("{pointer}: expected {e}, got {a}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn numbers_compare_within_tolerance() {
+        let expected = json!({"area": 1.234567890123456});
+        assert!(compare(&expected, &json!({"area": 1.2345678901235}), 1e-9).is_empty());
+        assert_eq!(compare(&expected, &json!({"area": 1.24}), 1e-9).len(), 1);
+    }
+
+    #[test]
+    fn reports_missing_and_unexpected_fields_by_pointer() {
+        let expected = json!({"a": {"b": 1}, "c": [1, 2]});
+        let actual = json!({"a": {"d": 1}, "c": [1, 3]});
+        let diffs = compare(&expected, &actual, FLOAT_TOLERANCE);
+        assert!(diffs.contains(&"/a/b: missing".to_string()));
+        assert!(diffs.contains(&"/a/d: unexpected field".to_string()));
+        assert!(diffs.iter().any(|d| d.starts_with("/c/1:")));
+    }
+
+    #[test]
+    fn normalize_rewrites_paths_keeps_order_and_redacts() {
+        let mut response = json!({
+            "server": {"version": "9.9.9", "uptime_secs": 42},
+            "results": [
+                {"source_file": "/tmp/fixtures/b.rs"},
+                {"source_file": "/tmp/fixtures/a.rs"}
+            ]
+        });
+        normalize(&mut response, Path::new("/tmp/fixtures"));
+        assert_eq!(response["server"]["version"], "<redacted>");
+        assert_eq!(response["server"]["uptime_secs"], "<redacted>");
+        let mut timings = json!({"tools": {"api_search": {"calls": 2, "max_ms": 7}}});
+        normalize(&mut timings, Path::new("/tmp/fixtures"));
+        assert_eq!(timings["tools"]["api_search"]["max_ms"], "<redacted>");
+        assert_eq!(timings["tools"]["api_search"]["calls"], 2);
+        assert_eq!(response["results"][0]["source_file"], "<root>/b.rs");
+        assert_eq!(response["results"][1]["source_file"], "<root>/a.rs");
+    }
+}
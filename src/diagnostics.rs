@@ -0,0 +1,212 @@
+//! Curated explanations of the rustc errors users hit most when calling into
+//! the library, for the `explain_error` tool.
+//!
+//! The table only says what an error class means and how it's usually fixed.
+//! Anything specific to the library (which feature gates an item, what a
+//! misspelled name probably was) comes from the index, in
+//! [`crate::toolkit::AmariToolkit::explain_error`].
+
+/// One class of compiler error.
+#[derive(Debug)]
+pub struct KnownError {
+    /// rustc error code, e.g. `E0432`, or a label for code-less diagnostics.
+    pub code: &'static str,
+    /// Phrases identifying the error when the message has no code.
+    pub patterns: &'static [&'static str],
+    pub title: &'static str,
+    pub causes: &'static [&'static str],
+    pub fixes: &'static [&'static str],
+}
+
+pub const KNOWN_ERRORS: &[KnownError] = &[
+    KnownError {
+        code: "E0432",
+        patterns: &["unresolved import"],
+        title: "Unresolved import",
+        causes: &[
+            "The item is behind a Cargo feature that isn't enabled",
+            "The item was renamed, moved to another module, or removed",
+            "The crate isn't a dependency, or is spelled with '-' instead of '_'",
+        ],
+        fixes: &[
+            "Enable the feature listed for the item below",
+            "Import from the path the index reports for the item",
+        ],
+    },
+    KnownError {
+        code: "E0433",
+        patterns: &["failed to resolve"],
+        title: "Failed to resolve a path",
+        causes: &[
+            "A module or crate in the path doesn't exist or isn't enabled",
+            "The path is missing a `use` for its first segment",
+        ],
+        fixes: &[
+            "Check the module path with module_overview",
+            "Enable the feature gating the crate or module",
+        ],
+    },
+    KnownError {
+        code: "E0412",
+        patterns: &["cannot find type"],
+        title: "Type not found in scope",
+        causes: &[
+            "The type isn't imported",
+            "The type is feature-gated or was renamed",
+        ],
+        fixes: &["Add a `use` for the full path reported below"],
+    },
+    KnownError {
+        code: "E0425",
+        patterns: &["cannot find function", "cannot find value"],
+        title: "Function or value not found in scope",
+        causes: &[
+            "The function isn't imported, or is an associated function that needs `Type::` in front",
+            "The function is feature-gated or was renamed",
+        ],
+        fixes: &["Call it through the path reported below"],
+    },
+    KnownError {
+        code: "E0599",
+        patterns: &["no method named", "no function or associated item named"],
+        title: "Method not found for this type",
+        causes: &[
+            "The method belongs to a trait that isn't in scope",
+            "The method exists on a different type, or only for other generic arguments",
+            "The impl block providing it is feature-gated",
+        ],
+        fixes: &[
+            "Import the trait that provides the method",
+            "Check which types implement the method in the items below",
+        ],
+    },
+    KnownError {
+        code: "E0277",
+        patterns: &["the trait bound", "is not satisfied"],
+        title: "Trait bound not satisfied",
+        causes: &[
+            "The type doesn't implement a trait the function requires",
+            "The implementation exists only behind a feature or for other generic arguments",
+        ],
+        fixes: &["Look up the trait with type_info to see its implementors"],
+    },
+    KnownError {
+        code: "E0308",
+        patterns: &["mismatched types"],
+        title: "Mismatched types",
+        causes: &[
+            "Values of the same generic type with different type or const parameters (for example `Vec<u32>` and `Vec<u64>`, or `[u8; 3]` and `[u8; 4]`) are distinct types and can't be mixed",
+            "A function returns a wrapper type such as `Option` or `Result`",
+        ],
+        fixes: &[
+            "Construct both values with the same generic parameters",
+            "Compare the expected signature with type_info",
+        ],
+    },
+    KnownError {
+        code: "E0107",
+        patterns: &["generic argument", "generic arguments"],
+        title: "Wrong number of generic arguments",
+        causes: &[
+            "A const-generic type was written with too few or too many parameters",
+        ],
+        fixes: &["Use the generics shown in the item's signature below"],
+    },
+    KnownError {
+        code: "deprecated",
+        patterns: &["use of deprecated"],
+        title: "Use of a deprecated item",
+        causes: &["The item still works but is scheduled for removal"],
+        fixes: &["Follow the deprecation note reported for the item below"],
+    },
+];
+
+/// The error code and candidate library names in a compiler message.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ParsedMessage {
+    pub code: Option<String>,
+    /// Identifiers taken from backticked spans, generics stripped, in order.
+    pub names: Vec<String>,
+}
+
+/// Find the `E0000` code and the identifiers quoted in backticks.
+pub fn parse_message(message: &str) -> ParsedMessage {
+    let code = message
+        .match_indices('E')
+        .map(|(i, _)| &message[i..])
+        .find(|rest| rest.len() >= 5 && rest.as_bytes()[1..5].iter().all(u8::is_ascii_digit))
+        .map(|rest| rest[..5].to_string());
+
+    let mut names = Vec::new();
+    for quoted in message.split('`').skip(1).step_by(2) {
+        let without_generics = quoted.split('<').next().unwrap_or(quoted);
+        for segment in without_generics.split("::") {
+            let segment = segment.trim_start_matches(['&', '*']).trim();
+            if is_identifier(segment) && !names.iter().any(|n| n == segment) {
+                names.push(segment.to_string());
+            }
+        }
+    }
+    ParsedMessage { code, names }
+}
+
+/// The table entry for `code`, or else the first whose pattern occurs in
+/// `message`.
+pub fn lookup(code: Option<&str>, message: &str) -> Option<&'static KnownError> {
+    let lower = message.to_lowercase();
+    code.and_then(|code| KNOWN_ERRORS.iter().find(|e| e.code == code))
+        .or_else(|| {
+            KNOWN_ERRORS
+                .iter()
+                .find(|e| e.patterns.iter().any(|p| lower.contains(p)))
+        })
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !matches!(s, "self" | "crate" | "super" | "mut" | "dyn" | "impl")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_code_and_backticked_names() {
+        let parsed = parse_message(
+            "error[E0599]: no method named `geometric_product` found for struct `Rotor<3, 0, 0>` in the current scope",
+        );
+        assert_eq!(parsed.code.as_deref(), Some("E0599"));
+        assert_eq!(parsed.names, vec!["geometric_product", "Rotor"]);
+
+        let parsed = parse_message("unresolved import `amari_core::gpu::Engine`");
+        assert_eq!(parsed.code, None);
+        assert_eq!(parsed.names, vec!["amari_core", "gpu", "Engine"]);
+    }
+
+    #[test]
+    fn looks_up_by_code_then_pattern() {
+        assert_eq!(
+            lookup(Some("E0432"), "").unwrap().title,
+            "Unresolved import"
+        );
+        assert_eq!(
+            lookup(None, "error: cannot find type `Foo` in this scope")
+                .unwrap()
+                .code,
+            "E0412"
+        );
+        assert!(lookup(Some("E9999"), "something else entirely").is_none());
+    }
+
+    #[test]
+    fn codes_are_unique() {
+        for (i, error) in KNOWN_ERRORS.iter().enumerate() {
+            assert!(KNOWN_ERRORS[i + 1..].iter().all(|e| e.code != error.code));
+        }
+    }
+}
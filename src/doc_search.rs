@@ -0,0 +1,323 @@
+//! Ranked full-text search over the library's documentation.
+//!
+//! [`DocIndex`] is a small in-memory inverted index over item doc comments
+//! and module docs (a crate's docs are its `lib` module's), scored with BM25. An item's name counts as
+//! part of its text, so `rotor normalize` finds `Rotor::normalize` even when
+//! its doc comment never repeats the words. Documents refer back to the
+//! [`ApiIndex`] by name rather than by reference, so the search index can be
+//! cached next to the index it was built from.
+
+use crate::parser::index::{ApiIndex, ModuleInfo, Validated};
+use std::collections::HashMap;
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 length normalization.
+const B: f64 = 0.75;
+/// Characters of context shown around the first matching term.
+const SNIPPET_CHARS: usize = 160;
+
+/// What a document is the documentation of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocRef {
+    /// `ordinal`-th item in `items_by_name[name]`.
+    Item { name: String, ordinal: usize },
+    /// A module by its path from the crate root module, e.g. `lib::shapes`.
+    Module { crate_name: String, path: String },
+}
+
+#[derive(Debug)]
+pub struct Doc {
+    pub target: DocRef,
+    /// The crate the document belongs to.
+    pub crate_name: String,
+    /// Item or module name, indexed along with `text`.
+    pub title: String,
+    pub text: String,
+    length: usize,
+}
+
+/// A scored match.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DocHit {
+    pub doc: usize,
+    pub score: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct DocIndex {
+    docs: Vec<Doc>,
+    /// Term → (document, term frequency).
+    postings: HashMap<String, Vec<(usize, usize)>>,
+    avg_length: f64,
+}
+
+impl DocIndex {
+    pub fn build(index: &ApiIndex<Validated>) -> Self {
+        let mut docs = Vec::new();
+        for info in &index.crates {
+            collect_modules(&info.name, &info.modules, "", &mut docs);
+        }
+        let mut names: Vec<&String> = index.items_by_name.keys().collect();
+        names.sort();
+        for name in names {
+            for (ordinal, item) in index.items_by_name[name].iter().enumerate() {
+                let crate_name = item.full_path.split("::").next().unwrap_or_default();
+                docs.push((
+                    DocRef::Item {
+                        name: name.clone(),
+                        ordinal,
+                    },
+                    crate_name.to_string(),
+                    name.clone(),
+                    item.doc_comment.clone(),
+                ));
+            }
+        }
+
+        let mut built = DocIndex::default();
+        for (target, crate_name, title, text) in docs {
+            built.add(target, crate_name, &title, text);
+        }
+        built.avg_length = if built.docs.is_empty() {
+            0.0
+        } else {
+            built.docs.iter().map(|d| d.length).sum::<usize>() as f64 / built.docs.len() as f64
+        };
+        built
+    }
+
+    fn add(&mut self, target: DocRef, crate_name: String, title: &str, text: String) {
+        let id = self.docs.len();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let tokens: Vec<String> = tokenize(title).into_iter().chain(tokenize(&text)).collect();
+        for token in &tokens {
+            *counts.entry(token.clone()).or_default() += 1;
+        }
+        for (term, count) in counts {
+            self.postings.entry(term).or_default().push((id, count));
+        }
+        self.docs.push(Doc {
+            target,
+            crate_name,
+            title: title.to_string(),
+            text,
+            length: tokens.len(),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    pub fn doc(&self, id: usize) -> &Doc {
+        &self.docs[id]
+    }
+
+    pub fn docs(&self) -> &[Doc] {
+        &self.docs
+    }
+
+    /// Documents matching any query term, best first, keeping those for
+    /// which `keep` returns true.
+    pub fn search(&self, query: &str, keep: impl Fn(&Doc) -> bool) -> Vec<DocHit> {
+        let total = self.docs.len() as f64;
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f64;
+            let idf = ((total - df + 0.5) / (df + 0.5) + 1.0).ln();
+            for &(doc, tf) in postings {
+                let length = self.docs[doc].length as f64;
+                let tf = tf as f64;
+                let norm = K1 * (1.0 - B + B * length / self.avg_length.max(1.0));
+                *scores.entry(doc).or_default() += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut hits: Vec<DocHit> = scores
+            .into_iter()
+            .filter(|(doc, _)| keep(&self.docs[*doc]))
+            .map(|(doc, score)| DocHit { doc, score })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.doc.cmp(&b.doc)));
+        hits
+    }
+}
+
+fn collect_modules(
+    crate_name: &str,
+    modules: &[ModuleInfo],
+    parent: &str,
+    out: &mut Vec<(DocRef, String, String, String)>,
+) {
+    for module in modules {
+        let path = if parent.is_empty() {
+            module.name.clone()
+        } else {
+            format!("{parent}::{}", module.name)
+        };
+        if !module.module_docs.is_empty() {
+            out.push((
+                DocRef::Module {
+                    crate_name: crate_name.to_string(),
+                    path: path.clone(),
+                },
+                crate_name.to_string(),
+                // The root module is titled by its crate.
+                if parent.is_empty() {
+                    crate_name.to_string()
+                } else {
+                    module.name.clone()
+                },
+                module.module_docs.clone(),
+            ));
+        }
+        collect_modules(crate_name, &module.submodules, &path, out);
+    }
+}
+
+/// Lowercase words of `text`, splitting on non-alphanumerics and on
+/// camel-case boundaries; `GeometricProduct` also yields `geometricproduct`.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let mut parts = Vec::new();
+        let mut start = 0;
+        let chars: Vec<(usize, char)> = word.char_indices().collect();
+        for pair in chars.windows(2) {
+            let ((_, prev), (i, c)) = (pair[0], pair[1]);
+            if prev.is_lowercase() && c.is_uppercase() {
+                parts.push(&word[start..i]);
+                start = i;
+            }
+        }
+        parts.push(&word[start..]);
+        if parts.len() > 1 {
+            tokens.push(word.to_lowercase());
+        }
+        tokens.extend(parts.into_iter().map(str::to_lowercase));
+    }
+    tokens
+}
+
+/// About [`SNIPPET_CHARS`] characters of `text` around the first query term,
+/// whitespace collapsed, with `…` marking cuts. The start of `text` when no
+/// term occurs in it.
+pub fn snippet(text: &str, query: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let chars: Vec<char> = flat.chars().collect();
+    // Lowercasing can change byte and character counts (`İ` becomes two
+    // characters), so record where each original character starts.
+    let mut lower = String::with_capacity(flat.len());
+    let mut starts = Vec::with_capacity(chars.len());
+    for c in &chars {
+        starts.push(lower.len());
+        lower.extend(c.to_lowercase());
+    }
+    let position = tokenize(query)
+        .iter()
+        .filter_map(|term| lower.find(term.as_str()))
+        .min()
+        .map(|byte| {
+            starts
+                .partition_point(|&start| start <= byte)
+                .saturating_sub(1)
+        })
+        .unwrap_or(0);
+
+    if chars.len() <= SNIPPET_CHARS {
+        return flat;
+    }
+    let start = position.saturating_sub(SNIPPET_CHARS / 4);
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+    let start = end.saturating_sub(SNIPPET_CHARS);
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.extend(&chars[start..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizes_words_and_camel_case() {
+        assert_eq!(
+            tokenize("GeometricProduct of two_vectors!"),
+            vec![
+                "geometricproduct",
+                "geometric",
+                "product",
+                "of",
+                "two",
+                "vectors"
+            ]
+        );
+    }
+
+    #[test]
+    fn ranks_rarer_and_repeated_terms_higher() {
+        let mut index = DocIndex::default();
+        for (name, text) in [
+            ("normalize", "Normalize the rotor to unit magnitude."),
+            ("reverse", "Reverse the rotor."),
+            ("compose", "Compose two rotors."),
+        ] {
+            index.add(
+                DocRef::Item {
+                    name: name.to_string(),
+                    ordinal: 0,
+                },
+                "core".to_string(),
+                name,
+                text.to_string(),
+            );
+        }
+        index.avg_length = 5.0;
+
+        let hits = index.search("normalize rotor", |_| true);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            index.doc(hits[0].doc).text,
+            "Normalize the rotor to unit magnitude."
+        );
+        assert!(hits[0].score > hits[1].score);
+        assert!(index
+            .search("rotor", |d| d.crate_name == "other")
+            .is_empty());
+    }
+
+    #[test]
+    fn snippet_centers_on_first_match() {
+        let text = format!("{} rotor {}", "lead ".repeat(60), "tail ".repeat(60));
+        let cut = snippet(&text, "rotor");
+        assert!(cut.starts_with('…') && cut.ends_with('…'));
+        assert!(cut.contains("rotor"));
+        assert_eq!(snippet("Short text.", "missing"), "Short text.");
+    }
+
+    #[test]
+    fn snippet_finds_match_after_case_expanding_characters() {
+        let text = format!("{} rotor {}", "İ".repeat(200), "tail ".repeat(60));
+        assert!(snippet(&text, "rotor").contains("rotor"));
+    }
+}
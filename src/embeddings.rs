@@ -0,0 +1,160 @@
+//! Vector embeddings for natural-language search over the docs.
+//!
+//! `search_docs` in `semantic` mode embeds every indexed document and the
+//! query with an [`EmbeddingProvider`] and ranks by cosine similarity. A
+//! program embedding this crate can plug in a real model (a local ONNX
+//! runtime, a hosted embeddings API) through
+//! [`crate::tools::SharedState::with_embeddings`]; without one the server
+//! uses [`BagOfWords`], which needs no model and no network.
+
+use crate::doc_search::tokenize;
+
+/// Turns text into fixed-length vectors; similar texts get nearby vectors.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Reported as `provider` in semantic search results.
+    fn name(&self) -> &str;
+
+    /// One vector per text, all the same length. Called once with every
+    /// document on the first semantic search, then once per query.
+    fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>>;
+}
+
+/// Dimensions of a [`BagOfWords`] vector.
+const BUCKETS: usize = 1024;
+
+/// Words too common in questions and docs to say anything about an item.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does", "find", "for", "from",
+    "function", "how", "i", "in", "is", "it", "me", "method", "of", "on", "or", "that", "the",
+    "this", "to", "type", "use", "what", "which", "with",
+];
+
+/// The fallback provider: stemmed, stopword-free word counts hashed into
+/// [`BUCKETS`] dimensions. It matches `normalizes a rotor` to
+/// `Rotor::normalize`, which keyword search misses, but knows no synonyms.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BagOfWords;
+
+impl BagOfWords {
+    fn vector(text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; BUCKETS];
+        for token in tokenize(text) {
+            if STOPWORDS.contains(&token.as_str()) {
+                continue;
+            }
+            let stem = stem(&token);
+            vector[bucket(stem)] += 1.0;
+        }
+        // Dampen repeated words so one long doc comment can't dominate.
+        for value in vector.iter_mut().filter(|v| **v > 0.0) {
+            *value = 1.0 + value.ln();
+        }
+        normalize(&mut vector);
+        vector
+    }
+}
+
+impl EmbeddingProvider for BagOfWords {
+    fn name(&self) -> &str {
+        "bag-of-words"
+    }
+
+    fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| Self::vector(text)).collect())
+    }
+}
+
+/// Strip common English suffixes so word forms share a bucket:
+/// `normalizes`, `normalized` and `normalization` all become `normal`.
+fn stem(word: &str) -> &str {
+    const SUFFIXES: &[&str] = &[
+        "ization", "isation", "ations", "ation", "izing", "ising", "ized", "ised", "izes", "ises",
+        "ize", "ise", "ing", "ed",
+    ];
+    for suffix in SUFFIXES {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if stem.len() >= 3 {
+                return stem;
+            }
+        }
+    }
+    // `-es` only after sibilants (`matches`), else just `-s` (`shapes`).
+    for sibilant in ["sses", "xes", "zes", "ches", "shes"] {
+        if word.ends_with(sibilant) {
+            return &word[..word.len() - 2];
+        }
+    }
+    match word.strip_suffix('s') {
+        Some(stem) if stem.len() >= 3 && !stem.ends_with('s') => stem,
+        _ => word,
+    }
+}
+
+/// FNV-1a of `word`, reduced to a bucket.
+fn bucket(word: &str) -> usize {
+    let hash = word.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    (hash % BUCKETS as u64) as usize
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// Cosine similarity; 0 when either vector is all zeros or the lengths differ.
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms =
+        a.iter().map(|v| v * v).sum::<f32>().sqrt() * b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stems_common_word_forms_together() {
+        for word in ["normalize", "normalizes", "normalized", "normalization"] {
+            assert_eq!(stem(word), "normal", "{word}");
+        }
+        assert_eq!(stem("shapes"), "shape");
+        assert_eq!(stem("matches"), "match");
+        assert_eq!(stem("class"), "class");
+        assert_eq!(stem("is"), "is");
+    }
+
+    #[test]
+    fn bag_of_words_ranks_question_against_docs() {
+        let provider = BagOfWords;
+        let vectors = provider
+            .embed(&[
+                "find me the function that normalizes a rotor",
+                "normalize Normalize the rotor to unit magnitude.",
+                "reverse Reverse the order of the blades.",
+            ])
+            .unwrap();
+        let normalize = cosine(&vectors[0], &vectors[1]);
+        let reverse = cosine(&vectors[0], &vectors[2]);
+        assert!(normalize > 0.5, "{normalize}");
+        assert_eq!(reverse, 0.0);
+    }
+
+    #[test]
+    fn cosine_handles_zero_and_mismatched_vectors() {
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine(&[1.0], &[1.0, 0.0]), 0.0);
+        assert!((cosine(&[1.0, 1.0], &[2.0, 2.0]) - 1.0).abs() < 1e-6);
+    }
+}
@@ -17,6 +17,15 @@ manifest file and it will index the full public API surface.
 */
 
 pub mod config;
+pub mod conformance;
+pub mod diagnostics;
+pub mod doc_search;
+pub mod embeddings;
 pub mod mcp_pmcp;
 pub mod parser;
+pub mod repl;
+pub mod resources;
+pub mod self_test;
+pub mod toolkit;
 pub mod tools;
+pub mod watch;
@@ -1,6 +1,8 @@
+use amari_mcp::self_test::Outcome;
 use anyhow::Result;
 use clap::Parser;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -21,6 +23,18 @@ struct Cli {
     /// Log level
     #[arg(long, default_value = "info")]
     log_level: String,
+
+    /// Log output format
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, including the active span fields
+    Json,
 }
 
 #[derive(Parser)]
@@ -29,6 +43,24 @@ enum Command {
     Serve,
     /// Validate that the manifest and source are parseable
     Check,
+    /// Print the tools the manifest enables
+    ListTools {
+        /// Print full tool metadata, including input schemas, as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the manifest's settings and paths without indexing
+    ValidateConfig,
+    /// Call tools interactively from a prompt
+    Repl,
+    /// Run a smoke test of every tool against the index
+    SelfTest,
+    /// Time index construction
+    Bench {
+        /// Number of index builds to time
+        #[arg(long, default_value_t = 5)]
+        iterations: usize,
+    },
 }
 
 #[tokio::main]
@@ -36,19 +68,45 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // Initialize logging — stderr only so stdout is clean for MCP JSON-RPC
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| format!("amari_mcp={}", cli.log_level).into()),
-        )
-        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
-        .init();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| format!("amari_mcp={}", cli.log_level).into()),
+    );
+    match cli.log_format {
+        LogFormat::Text => subscriber
+            .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+            .init(),
+        LogFormat::Json => subscriber
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .with_writer(std::io::stderr),
+            )
+            .init(),
+    }
 
     info!("Loading manifest from {:?}", cli.manifest);
     let manifest = amari_mcp::config::LibraryManifest::load(&cli.manifest)?;
     info!("Loaded manifest for {}", manifest.library.display_name);
 
-    match cli.command.as_ref().unwrap_or(&Command::Serve) {
+    let command = cli.command.as_ref().unwrap_or(&Command::Serve);
+    // Limits and caps are clamped to 1 at runtime; refuse to run rather than
+    // silently turn a mistyped 0 into 1. `validate-config` only reports them.
+    if !matches!(command, Command::ValidateConfig) {
+        let problems = manifest.server.problems();
+        if !problems.is_empty() {
+            anyhow::bail!("Invalid [server] settings: {}", problems.join("; "));
+        }
+
+        if let Some(threads) = manifest.server.threads {
+            amari_mcp::parser::configure_threads(threads)?;
+            info!("Indexing with {threads} worker thread(s)");
+        }
+    }
+
+    match command {
         Command::Serve => {
             let index = amari_mcp::parser::build_index(&manifest, &cli.manifest)?;
             let validated = index.validate()?;
@@ -80,6 +138,18 @@ async fn main() -> Result<()> {
                     if parse_error_count > 0 {
                         println!("\n{parse_error_count} parse warning(s) (index still usable)");
                     }
+                    let root_cargo_toml = manifest
+                        .resolve_source_path(&cli.manifest)
+                        .join(&manifest.workspace.root_cargo_toml);
+                    if let Ok(metadata) =
+                        amari_mcp::parser::workspace::read_workspace_metadata(&root_cargo_toml)
+                    {
+                        for drift in
+                            amari_mcp::parser::workspace::manifest_drift(&manifest, &metadata)
+                        {
+                            println!("WARNING: {drift}");
+                        }
+                    }
                     println!("\nCheck passed.");
                 }
                 Err(report) => {
@@ -90,11 +160,167 @@ async fn main() -> Result<()> {
                 }
             }
         }
+        Command::ListTools { json } => {
+            let index = amari_mcp::parser::build_index(&manifest, &cli.manifest)?;
+            let state = amari_mcp::tools::SharedState::new(index.validate()?, manifest);
+            let tools = amari_mcp::tools::build_tools(&state);
+            let infos: Vec<_> = tools
+                .iter()
+                .filter_map(|(_, handler)| handler.metadata())
+                .collect();
+
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&infos)?);
+            } else {
+                for info in &infos {
+                    println!(
+                        "{:<18} {}",
+                        info.name,
+                        info.description
+                            .as_deref()
+                            .and_then(|d| d.lines().next())
+                            .unwrap_or_default()
+                    );
+                }
+            }
+        }
+        Command::Repl => {
+            let index = amari_mcp::parser::build_index(&manifest, &cli.manifest)?;
+            let library_name = manifest.library.name.clone();
+            let state = amari_mcp::tools::SharedState::new(index.validate()?, manifest);
+            let tools = amari_mcp::tools::build_tools(&state);
+            amari_mcp::repl::run(&tools, &library_name).await?;
+        }
+        Command::SelfTest => {
+            let index = amari_mcp::parser::build_index(&manifest, &cli.manifest)?;
+            let state = amari_mcp::tools::SharedState::new(index.validate()?, manifest);
+            let tools = amari_mcp::tools::build_tools(&state);
+
+            let outcomes = amari_mcp::self_test::run(&state, &tools).await;
+            let mut failed = 0;
+            for (check, outcome) in &outcomes {
+                match outcome {
+                    Outcome::Passed => println!("PASS  {}: {}", check.tool, check.description),
+                    Outcome::Skipped => println!("SKIP  {}: not registered", check.tool),
+                    Outcome::Failed(reason) => {
+                        failed += 1;
+                        println!("FAIL  {}: {} ({reason})", check.tool, check.description);
+                    }
+                }
+            }
+            println!("\n{} check(s), {failed} failed", outcomes.len());
+            if failed > 0 {
+                std::process::exit(1);
+            }
+        }
+        Command::ValidateConfig => {
+            let problems = config_problems(&manifest, &cli.manifest);
+            if !problems.is_empty() {
+                for problem in &problems {
+                    eprintln!("ERROR: {problem}");
+                }
+                std::process::exit(1);
+            }
+            println!("Config valid.");
+        }
+        Command::Bench { iterations } => {
+            let iterations = (*iterations).max(1);
+            let mut timings = Vec::with_capacity(iterations);
+            let mut item_count = 0;
+            for _ in 0..iterations {
+                let started = Instant::now();
+                let validated =
+                    amari_mcp::parser::build_index(&manifest, &cli.manifest)?.validate()?;
+                timings.push(started.elapsed());
+                item_count = validated.stats().item_count;
+            }
+            timings.sort_unstable();
+
+            let total: std::time::Duration = timings.iter().sum();
+            println!("Indexed {item_count} items, {iterations} run(s)");
+            println!(
+                "  min {:.1} ms, median {:.1} ms, mean {:.1} ms, max {:.1} ms",
+                timings[0].as_secs_f64() * 1000.0,
+                timings[timings.len() / 2].as_secs_f64() * 1000.0,
+                total.as_secs_f64() * 1000.0 / iterations as f64,
+                timings[timings.len() - 1].as_secs_f64() * 1000.0,
+            );
+        }
     }
 
     Ok(())
 }
 
+/// Problems in the manifest that would stop the server from indexing or
+/// serving as configured.
+fn config_problems(
+    manifest: &amari_mcp::config::LibraryManifest,
+    manifest_path: &Path,
+) -> Vec<String> {
+    let mut problems = manifest.server.problems();
+
+    let source_root = manifest.resolve_source_path(manifest_path);
+    if source_root.is_dir() {
+        let root_cargo_toml = source_root.join(&manifest.workspace.root_cargo_toml);
+        if !root_cargo_toml.is_file() {
+            problems.push(format!("{} does not exist", root_cargo_toml.display()));
+        }
+        for resolved in manifest.all_user_facing_crates() {
+            if !source_root.join(&resolved.dir_name).is_dir() {
+                problems.push(format!("Crate directory not found: {}", resolved.dir_name));
+            }
+        }
+    } else {
+        problems.push(format!(
+            "library.source_path {} does not exist",
+            source_root.display()
+        ));
+    }
+
+    for path in manifest.resolve_allowed_paths() {
+        if !path.is_dir() {
+            problems.push(format!(
+                "workspace.allowed_paths: {} does not exist",
+                path.display()
+            ));
+        }
+    }
+
+    for path in manifest.resolve_project_roots() {
+        if !path.is_dir() {
+            problems.push(format!(
+                "server.project_roots: {} does not exist",
+                path.display()
+            ));
+        }
+    }
+
+    let manifest_dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut labels: Vec<&String> = manifest.baselines.keys().collect();
+    labels.sort();
+    for label in labels {
+        let path = manifest_dir.join(&manifest.baselines[label]);
+        if !path.is_dir() {
+            problems.push(format!(
+                "baselines.{label}: {} does not exist",
+                path.display()
+            ));
+        }
+    }
+
+    let mut names: Vec<&String> = manifest.workspaces.keys().collect();
+    names.sort();
+    for name in names {
+        if let Some(path) = manifest.resolve_workspace(name) {
+            if let Err(e) = amari_mcp::config::LibraryManifest::load(&path) {
+                problems.push(format!("workspaces.{name}: {e:#}"));
+            }
+        }
+    }
+
+    problems
+}
+
 fn count_crate_items(modules: &[amari_mcp::parser::index::ModuleInfo]) -> usize {
     modules
         .iter()
@@ -1,82 +1,367 @@
 use anyhow::Result;
-use pmcp::{Server, ServerCapabilities};
-use tracing::info;
+use async_trait::async_trait;
+use pmcp::error::TransportError;
+use pmcp::types::protocol::ResourceUpdatedParams;
+use pmcp::types::{
+    ClientRequest, Content, ListResourcesResult, Notification, ReadResourceResult, Request,
+    ResourceCapabilities, ResourceInfo, ServerNotification, SubscribeRequest, TransportMessage,
+    UnsubscribeRequest,
+};
+use pmcp::{
+    RequestHandlerExtra, ResourceHandler, Server, ServerCapabilities, StdioTransport, Transport,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncWriteExt, Stdout};
+use tokio::sync::{mpsc, Mutex};
+use tracing::{info, warn};
 
 use crate::config::LibraryManifest;
 use crate::parser::index::{ApiIndex, Validated};
-use crate::tools::{
-    api_search, browse_docs, dependency_graph, feature_map, module_overview, type_info,
-    usage_examples, SharedState,
-};
+use crate::resources::SourceResources;
+use crate::tools::plugin::PluginRegistry;
+use crate::tools::{build_tools_with_plugins, SharedState};
+use crate::watch::{self, SourceChange};
 
 /// Create and run the MCP server with the given validated index.
 pub async fn create_mcp_server(
     index: ApiIndex<Validated>,
     manifest: LibraryManifest,
 ) -> Result<()> {
-    let state = SharedState::new(index, manifest);
+    create_mcp_server_with_plugins(index, manifest, PluginRegistry::new()).await
+}
+
+/// Like [`create_mcp_server`], also installing the configured `plugins`.
+pub async fn create_mcp_server_with_plugins(
+    index: ApiIndex<Validated>,
+    manifest: LibraryManifest,
+    plugins: PluginRegistry,
+) -> Result<()> {
+    create_mcp_server_with_state(SharedState::new(index, manifest), plugins).await
+}
 
-    info!("Registering 7 MCP tools");
+/// Like [`create_mcp_server_with_plugins`], serving an already built `state`,
+/// e.g. one from [`SharedState::with_embeddings`].
+pub async fn create_mcp_server_with_state(
+    state: Arc<SharedState>,
+    plugins: PluginRegistry,
+) -> Result<()> {
+    let tools = build_tools_with_plugins(&state, &plugins);
 
-    let server = Server::builder()
+    info!(
+        "Registering {} MCP tools ({:?} profile)",
+        tools.len(),
+        state.manifest.server.profile
+    );
+
+    if let (Some(secs), Some(stats)) =
+        (state.manifest.server.stats_log_interval_secs, tools.stats())
+    {
+        let stats = stats.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(secs.max(1)));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                info!("Usage: {}", stats.summary());
+            }
+        });
+    }
+
+    let transport = NotifyingStdio::new();
+    let watching = state.manifest.server.watch_interval_secs;
+    if let Some(secs) = watching {
+        info!("Watching source files for changes every {secs}s");
+        let (changed, mut changes) = mpsc::unbounded_channel();
+        watch::spawn(state.clone(), Duration::from_secs(secs.max(1)), changed);
+        let stdout = transport.stdout.clone();
+        let subscriptions = transport.subscriptions.clone();
+        tokio::spawn(async move {
+            while let Some(change) = changes.recv().await {
+                let notification = match change {
+                    SourceChange::ListChanged => ServerNotification::ResourcesChanged,
+                    SourceChange::Updated(uri) if subscriptions.contains(&uri) => {
+                        ServerNotification::ResourceUpdated(ResourceUpdatedParams { uri })
+                    }
+                    SourceChange::Updated(_) => continue,
+                };
+                let message = TransportMessage::Notification(Notification::Server(notification));
+                if let Err(e) = write_message(&stdout, &message).await {
+                    warn!("Cannot send resource notification: {e}");
+                }
+            }
+        });
+    }
+
+    // Subscriptions and list changes are only reported while watching;
+    // set before `resources()`, which would otherwise advertise neither.
+    let capabilities = ServerCapabilities {
+        resources: Some(ResourceCapabilities {
+            subscribe: Some(watching.is_some()),
+            list_changed: Some(watching.is_some()),
+        }),
+        ..ServerCapabilities::default()
+    };
+    let mut builder = Server::builder()
         .name("amari-mcp")
         .version(env!("CARGO_PKG_VERSION"))
-        .capabilities(ServerCapabilities::default())
-        .tool(
-            "api_search",
-            api_search::ApiSearchHandler {
-                state: state.clone(),
-            },
-        )
-        .tool(
-            "type_info",
-            type_info::TypeInfoHandler {
-                state: state.clone(),
-            },
-        )
-        .tool(
-            "module_overview",
-            module_overview::ModuleOverviewHandler {
-                state: state.clone(),
-            },
-        )
-        .tool(
-            "feature_map",
-            feature_map::FeatureMapHandler {
-                state: state.clone(),
-            },
-        )
-        .tool(
-            "dependency_graph",
-            dependency_graph::DependencyGraphHandler {
-                state: state.clone(),
-            },
-        )
-        .tool(
-            "browse_docs",
-            browse_docs::BrowseDocsHandler {
-                state: state.clone(),
-            },
-        )
-        .tool(
-            "usage_examples",
-            usage_examples::UsageExamplesHandler {
-                state: state.clone(),
-            },
-        )
+        .capabilities(capabilities);
+
+    for handler in tools.shared_handlers() {
+        let name = handler.name().to_string();
+        builder = builder.tool(&name, handler);
+    }
+    builder = builder.resources(SourceResourceHandler(SourceResources::new(&state)));
+
+    let server = builder
         .build()
         .map_err(|e| anyhow::anyhow!("Failed to build MCP server: {e}"))?;
 
     info!("MCP server ready, starting stdio transport");
-    server.run_stdio().await?;
+    let serve = server.run(transport);
+    tokio::pin!(serve);
+
+    let signal = tokio::select! {
+        result = &mut serve => {
+            result?;
+            return Ok(());
+        }
+        signal = shutdown_signal() => signal,
+    };
+
+    let Some(in_flight) = tools.in_flight() else {
+        info!("Received {signal}, shutting down");
+        return Ok(());
+    };
+    info!(
+        "Received {signal}, draining {} in-flight tool call(s)",
+        in_flight.len()
+    );
+
+    // Keep the transport running while draining so running calls can
+    // respond; new calls are rejected by the registry.
+    let timeout = Duration::from_secs(state.manifest.server.drain_timeout_secs);
+    tokio::select! {
+        result = &mut serve => result?,
+        cancelled = in_flight.drain(timeout) => {
+            if cancelled > 0 {
+                warn!("Cancelled {cancelled} tool call(s) still running after {timeout:?}");
+            }
+        }
+    }
+
+    info!("Shutdown complete");
+    Ok(())
+}
+
+/// Serves the library's source files through `resources/list` and
+/// `resources/read`.
+struct SourceResourceHandler(SourceResources);
+
+#[async_trait]
+impl ResourceHandler for SourceResourceHandler {
+    async fn read(
+        &self,
+        uri: &str,
+        _extra: RequestHandlerExtra,
+    ) -> pmcp::Result<ReadResourceResult> {
+        let file = self
+            .0
+            .read(uri)
+            .map_err(|e| pmcp::Error::invalid_params(e.to_string()))?;
+        Ok(ReadResourceResult {
+            contents: vec![Content::Resource {
+                uri: file.uri,
+                text: Some(file.text),
+                mime_type: Some(file.mime_type.to_string()),
+            }],
+        })
+    }
+
+    async fn list(
+        &self,
+        cursor: Option<String>,
+        _extra: RequestHandlerExtra,
+    ) -> pmcp::Result<ListResourcesResult> {
+        let (entries, next_cursor) = self
+            .0
+            .list(cursor.as_deref())
+            .map_err(|e| pmcp::Error::invalid_params(e.to_string()))?;
+        Ok(ListResourcesResult {
+            resources: entries
+                .into_iter()
+                .map(|entry| ResourceInfo {
+                    uri: entry.uri,
+                    name: entry.name,
+                    description: None,
+                    mime_type: Some(entry.mime_type.to_string()),
+                })
+                .collect(),
+            next_cursor,
+        })
+    }
+}
+
+/// Newline-delimited JSON-RPC over stdin and stdout, as pmcp's
+/// [`StdioTransport`] speaks it, with stdout shared so the source watch can
+/// send notifications between the server's own messages.
+///
+/// pmcp answers `resources/subscribe` and `resources/unsubscribe` with an
+/// empty result and keeps no record, so the transport notes the URIs itself
+/// as the requests pass through.
+#[derive(Debug)]
+struct NotifyingStdio {
+    stdin: StdioTransport,
+    stdout: Arc<Mutex<Stdout>>,
+    subscriptions: Arc<Subscriptions>,
+}
+
+impl NotifyingStdio {
+    fn new() -> Self {
+        Self {
+            stdin: StdioTransport::new(),
+            stdout: Arc::new(Mutex::new(tokio::io::stdout())),
+            subscriptions: Arc::new(Subscriptions::default()),
+        }
+    }
+}
+
+/// Resource URIs the client subscribed to, without any range query.
+#[derive(Debug, Default)]
+struct Subscriptions(std::sync::Mutex<HashSet<String>>);
 
+impl Subscriptions {
+    /// Apply `message` if it is a subscribe or unsubscribe request.
+    fn observe(&self, message: &TransportMessage) {
+        let TransportMessage::Request {
+            request: Request::Client(request),
+            ..
+        } = message
+        else {
+            return;
+        };
+        match request.as_ref() {
+            ClientRequest::Subscribe(SubscribeRequest { uri }) => {
+                self.lock().insert(base_uri(uri).to_string());
+            }
+            ClientRequest::Unsubscribe(UnsubscribeRequest { uri }) => {
+                self.lock().remove(base_uri(uri));
+            }
+            _ => {}
+        }
+    }
+
+    fn contains(&self, uri: &str) -> bool {
+        self.lock().contains(base_uri(uri))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// `uri` without its `?lines=`/`bytes=`/`hash=` query.
+fn base_uri(uri: &str) -> &str {
+    uri.split_once('?').map_or(uri, |(base, _)| base)
+}
+
+#[async_trait]
+impl Transport for NotifyingStdio {
+    async fn send(&mut self, message: TransportMessage) -> pmcp::Result<()> {
+        write_message(&self.stdout, &message).await
+    }
+
+    async fn receive(&mut self) -> pmcp::Result<TransportMessage> {
+        let message = self.stdin.receive().await?;
+        self.subscriptions.observe(&message);
+        Ok(message)
+    }
+
+    async fn close(&mut self) -> pmcp::Result<()> {
+        self.stdout
+            .lock()
+            .await
+            .flush()
+            .await
+            .map_err(TransportError::from)?;
+        self.stdin.close().await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.stdin.is_connected()
+    }
+
+    fn transport_type(&self) -> &'static str {
+        "stdio"
+    }
+}
+
+/// Write `message` as one line, holding the lock so messages never interleave.
+async fn write_message(stdout: &Mutex<Stdout>, message: &TransportMessage) -> pmcp::Result<()> {
+    let mut line = StdioTransport::serialize_message(message)?;
+    line.push(b'\n');
+    let mut stdout = stdout.lock().await;
+    stdout
+        .write_all(&line)
+        .await
+        .map_err(TransportError::from)?;
+    stdout.flush().await.map_err(TransportError::from)?;
     Ok(())
 }
 
+/// Resolve on SIGINT (Ctrl-C) or, on Unix, SIGTERM; returns the signal name.
+async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = sigterm.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                warn!("Cannot listen for SIGTERM: {e}");
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use pmcp::types::RequestId;
+
+    fn request(request: ClientRequest) -> TransportMessage {
+        TransportMessage::Request {
+            id: RequestId::from(1i64),
+            request: Request::Client(Box::new(request)),
+        }
+    }
+
     #[test]
-    fn placeholder() {
-        // Server creation requires stdio transport; tested via integration tests
+    fn subscriptions_follow_subscribe_requests() {
+        let uri = "minilib://source/minilib-core/src/lib.rs";
+        let subscriptions = Subscriptions::default();
+        assert!(!subscriptions.contains(uri));
+
+        subscriptions.observe(&request(ClientRequest::Subscribe(SubscribeRequest {
+            uri: format!("{uri}?lines=1-10"),
+        })));
+        subscriptions.observe(&request(ClientRequest::Ping));
+        assert!(subscriptions.contains(uri));
+        assert!(!subscriptions.contains("minilib://source/minilib-core/src/shapes.rs"));
+
+        subscriptions.observe(&request(ClientRequest::Unsubscribe(UnsubscribeRequest {
+            uri: uri.to_string(),
+        })));
+        assert!(!subscriptions.contains(uri));
     }
 }
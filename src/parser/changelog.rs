@@ -0,0 +1,295 @@
+//! Release notes from the library checkout: `CHANGELOG.md` sections and git
+//! tags, for the `changelog` tool.
+//!
+//! The changelog is expected to follow Keep a Changelog loosely: a `##`
+//! heading per release (`## [0.9.0] - 2024-03-01`, `## v0.9.0`, `## 0.9.0
+//! (2024-03-01)`), `###` headings for kinds of change, and `-`/`*` bullets.
+//! Tags are read from the repository's ref files, so no `git` binary is needed.
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// One `##` section of the changelog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// Version without brackets or a leading `v`, or `Unreleased`.
+    pub version: String,
+    pub date: Option<String>,
+    pub sections: Vec<Section>,
+}
+
+/// A `###` heading and its bullets. Bullets before any `###` heading go in a
+/// section headed `Changes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub heading: String,
+    pub entries: Vec<String>,
+}
+
+/// Releases in the order they appear, normally newest first.
+pub fn parse_changelog(text: &str) -> Vec<Release> {
+    let mut releases: Vec<Release> = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(heading) = trimmed.strip_prefix("## ") {
+            releases.push(release_heading(heading));
+            continue;
+        }
+        let Some(release) = releases.last_mut() else {
+            continue;
+        };
+        if let Some(heading) = trimmed.strip_prefix("### ") {
+            release.sections.push(Section {
+                heading: heading.trim().to_string(),
+                entries: Vec::new(),
+            });
+        } else if let Some(entry) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            if release.sections.is_empty() {
+                release.sections.push(Section {
+                    heading: "Changes".to_string(),
+                    entries: Vec::new(),
+                });
+            }
+            let section = release.sections.last_mut().expect("pushed above");
+            // Nested bullets are kept as separate entries.
+            section.entries.push(entry.trim().to_string());
+        } else if !trimmed.is_empty() && line.starts_with(char::is_whitespace) {
+            // Continuation of a wrapped bullet.
+            if let Some(last) = release
+                .sections
+                .last_mut()
+                .and_then(|s| s.entries.last_mut())
+            {
+                last.push(' ');
+                last.push_str(trimmed);
+            }
+        }
+    }
+    releases
+}
+
+fn release_heading(heading: &str) -> Release {
+    let mut words = heading.split_whitespace();
+    let version = words
+        .next()
+        .unwrap_or_default()
+        .trim_matches(|c| c == '[' || c == ']');
+    let version = version
+        .strip_prefix('v')
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(version);
+    let date = words
+        .map(|w| w.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+        .find(|w| is_date(w))
+        .map(str::to_string);
+    Release {
+        version: version.to_string(),
+        date,
+        sections: Vec::new(),
+    }
+}
+
+fn is_date(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+/// Compare versions numerically, component by component, treating missing
+/// components as 0 (`0.9` == `0.9.0`) and a pre-release as older than its
+/// release. `None` if either isn't a version.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a_core, a_pre) = split_version(a)?;
+    let (b_core, b_pre) = split_version(b)?;
+    let len = a_core.len().max(b_core.len());
+    let component = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    let core = (0..len)
+        .map(|i| component(&a_core, i).cmp(&component(&b_core, i)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal);
+    Some(core.then(match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(b),
+    }))
+}
+
+fn split_version(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let core = core
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    Some((core, pre))
+}
+
+/// Whether `version` falls in `(from, to]`; an absent bound is open.
+/// Non-version headings such as `Unreleased` only match when `to` is absent.
+pub fn in_range(version: &str, from: Option<&str>, to: Option<&str>) -> bool {
+    if split_version(version).is_none() {
+        return to.is_none() && version.eq_ignore_ascii_case("unreleased");
+    }
+    let after_from =
+        from.is_none_or(|from| compare_versions(version, from) == Some(Ordering::Greater));
+    let up_to = to.is_none_or(|to| {
+        matches!(
+            compare_versions(version, to),
+            Some(Ordering::Less | Ordering::Equal)
+        )
+    });
+    after_from && up_to
+}
+
+/// The version a tag names: `v0.9.0`, `0.9.0` and `amari-core-v0.9.0` all
+/// give `0.9.0`.
+pub fn tag_version(tag: &str) -> Option<&str> {
+    let candidate = match tag.rsplit_once('-') {
+        Some((_, last)) if split_version(last).is_some() => last,
+        _ => tag,
+    };
+    let candidate = candidate.strip_prefix('v').unwrap_or(candidate);
+    split_version(candidate).map(|_| candidate)
+}
+
+/// Tag names of the git repository at `repo`, sorted, from loose refs and
+/// `packed-refs`. Empty when `repo` isn't a git checkout. Worktrees (where
+/// `.git` is a file) read the tags of their main repository.
+pub fn read_tags(repo: &Path) -> Vec<String> {
+    let Some(git_dir) = common_git_dir(repo) else {
+        return Vec::new();
+    };
+    let mut tags = Vec::new();
+
+    let tags_dir = git_dir.join("refs/tags");
+    for entry in walkdir::WalkDir::new(&tags_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        if let Ok(name) = entry.path().strip_prefix(&tags_dir) {
+            tags.push(name.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    if let Ok(packed) = std::fs::read_to_string(git_dir.join("packed-refs")) {
+        tags.extend(
+            packed
+                .lines()
+                .filter_map(|line| line.split_once(' '))
+                .filter_map(|(_, name)| name.strip_prefix("refs/tags/"))
+                .map(str::to_string),
+        );
+    }
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn common_git_dir(repo: &Path) -> Option<PathBuf> {
+    let dot_git = repo.join(".git");
+    let git_dir = if dot_git.is_dir() {
+        dot_git
+    } else {
+        let pointer = std::fs::read_to_string(&dot_git).ok()?;
+        let target = pointer.trim().strip_prefix("gitdir:")?.trim();
+        repo.join(target)
+    };
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => Some(git_dir.join(common.trim())),
+        Err(_) => Some(git_dir),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "\
+# Changelog
+
+## [Unreleased]
+### Added
+- Rotor interpolation
+
+## [0.9.0] - 2024-03-01
+### Added
+- `Rotor::slerp` for
+  interpolating rotations
+### Removed
+- `Rotor::lerp`
+
+## v0.8.1 (2024-01-15)
+- Fix sign of the reverse
+
+## 0.8.0
+### Changed
+* Renamed `Mv` to `Multivector`
+";
+
+    #[test]
+    fn parses_release_headings_sections_and_bullets() {
+        let releases = parse_changelog(CHANGELOG);
+        let versions: Vec<&str> = releases.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, vec!["Unreleased", "0.9.0", "0.8.1", "0.8.0"]);
+        assert_eq!(releases[1].date.as_deref(), Some("2024-03-01"));
+        assert_eq!(
+            releases[1].sections[0].entries,
+            vec!["`Rotor::slerp` for interpolating rotations"]
+        );
+        assert_eq!(releases[2].date.as_deref(), Some("2024-01-15"));
+        assert_eq!(releases[2].sections[0].heading, "Changes");
+        assert_eq!(releases[3].sections[0].entries.len(), 1);
+    }
+
+    #[test]
+    fn compares_and_ranges_versions() {
+        assert_eq!(compare_versions("0.9", "0.9.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("0.10.0", "0.9.1"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("1.0.0-rc1", "1.0.0"), Some(Ordering::Less));
+        assert_eq!(compare_versions("Unreleased", "1.0"), None);
+
+        let in_08_09 = |v| in_range(v, Some("0.8"), Some("0.9"));
+        assert!(in_08_09("0.9.0"));
+        assert!(in_08_09("0.8.1"));
+        assert!(!in_08_09("0.8.0"));
+        assert!(!in_08_09("Unreleased"));
+        assert!(in_range("Unreleased", Some("0.9"), None));
+    }
+
+    #[test]
+    fn extracts_versions_from_tags() {
+        assert_eq!(tag_version("v0.9.0"), Some("0.9.0"));
+        assert_eq!(tag_version("0.9.0"), Some("0.9.0"));
+        assert_eq!(tag_version("amari-core-v0.9.0"), Some("0.9.0"));
+        assert_eq!(tag_version("nightly"), None);
+    }
+
+    #[test]
+    fn reads_loose_and_packed_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = dir.path().join(".git");
+        std::fs::create_dir_all(git.join("refs/tags/release")).unwrap();
+        std::fs::write(git.join("refs/tags/v0.9.0"), "abc\n").unwrap();
+        std::fs::write(git.join("refs/tags/release/1.0"), "abc\n").unwrap();
+        std::fs::write(
+            git.join("packed-refs"),
+            "# pack-refs with: peeled\nabc refs/tags/v0.8.0\n^def\nabc refs/heads/main\n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_tags(dir.path()),
+            vec!["release/1.0", "v0.8.0", "v0.9.0"]
+        );
+        assert!(read_tags(&dir.path().join("missing")).is_empty());
+    }
+}
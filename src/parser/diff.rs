@@ -0,0 +1,142 @@
+use crate::parser::index::{ApiIndex, ApiItem, Validated};
+use std::collections::BTreeMap;
+
+/// Differences in the public API between two indexes of the same library.
+#[derive(Debug, Default)]
+pub struct ApiDiff<'a> {
+    /// Items present only in the newer index.
+    pub added: Vec<&'a ApiItem>,
+    /// Items present only in the older index.
+    pub removed: Vec<&'a ApiItem>,
+    /// Items present in both whose signature changed.
+    pub changed: Vec<ChangedItem<'a>>,
+}
+
+/// An item whose rendered signature differs between the two indexes.
+#[derive(Debug)]
+pub struct ChangedItem<'a> {
+    pub old: &'a ApiItem,
+    pub new: &'a ApiItem,
+}
+
+/// Compare two indexes item by item, keyed on `full_path`.
+///
+/// Several items can share a path (e.g. the same trait implemented for
+/// different generic arguments); such a path counts as changed when its
+/// set of signatures differs. Results are sorted by path.
+pub fn diff_indexes<'a>(old: &'a ApiIndex<Validated>, new: &'a ApiIndex<Validated>) -> ApiDiff<'a> {
+    let old_items = items_by_path(old);
+    let new_items = items_by_path(new);
+
+    let mut diff = ApiDiff::default();
+    for (path, old_group) in &old_items {
+        match new_items.get(path) {
+            None => diff.removed.extend(old_group.iter().copied()),
+            Some(new_group) if signatures(old_group) != signatures(new_group) => {
+                diff.changed.push(ChangedItem {
+                    old: old_group[0],
+                    new: new_group[0],
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for (path, new_group) in &new_items {
+        if !old_items.contains_key(path) {
+            diff.added.extend(new_group.iter().copied());
+        }
+    }
+    diff
+}
+
+fn items_by_path(index: &ApiIndex<Validated>) -> BTreeMap<&str, Vec<&ApiItem>> {
+    let mut by_path: BTreeMap<&str, Vec<&ApiItem>> = BTreeMap::new();
+    for item in index.items_by_name.values().flatten() {
+        by_path
+            .entry(item.full_path.as_str())
+            .or_default()
+            .push(item);
+    }
+    by_path
+}
+
+fn signatures<'a>(items: &[&'a ApiItem]) -> Vec<&'a str> {
+    let mut sigs: Vec<&str> = items.iter().map(|item| item.signature.as_str()).collect();
+    sigs.sort_unstable();
+    sigs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::index::{collect_items_from_modules, ItemKind, ModuleInfo, Unvalidated};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn function(name: &str, signature: &str) -> ApiItem {
+        ApiItem {
+            kind: ItemKind::Function {
+                is_async: false,
+                is_unsafe: false,
+            },
+            name: name.to_string(),
+            full_path: format!("lib::{name}"),
+            signature: signature.to_string(),
+            doc_comment: String::new(),
+            feature_gate: None,
+            generics: None,
+            deprecated: None,
+            source_file: PathBuf::from("src/lib.rs"),
+            line_number: 1,
+        }
+    }
+
+    fn index_of(items: Vec<ApiItem>) -> ApiIndex<Validated> {
+        let module = ModuleInfo {
+            name: "lib".to_string(),
+            path: PathBuf::from("src/lib.rs"),
+            module_docs: String::new(),
+            items,
+            submodules: Vec::new(),
+            feature_gate: None,
+        };
+        let mut items_by_name = HashMap::new();
+        collect_items_from_modules(std::slice::from_ref(&module), &mut items_by_name);
+        ApiIndex::<Unvalidated>::new("lib".to_string(), Vec::new(), items_by_name, Vec::new())
+            .validate()
+            .unwrap()
+    }
+
+    #[test]
+    fn detects_added_removed_and_changed_items() {
+        let old = index_of(vec![
+            function("kept", "pub fn kept ()"),
+            function("dropped", "pub fn dropped ()"),
+            function("reshaped", "pub fn reshaped (x : f64)"),
+        ]);
+        let new = index_of(vec![
+            function("kept", "pub fn kept ()"),
+            function("fresh", "pub fn fresh ()"),
+            function("reshaped", "pub fn reshaped (x : f32)"),
+        ]);
+
+        let diff = diff_indexes(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].name, "fresh");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].name, "dropped");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].old.signature, "pub fn reshaped (x : f64)");
+        assert_eq!(diff.changed[0].new.signature, "pub fn reshaped (x : f32)");
+    }
+
+    #[test]
+    fn identical_indexes_have_empty_diff() {
+        let old = index_of(vec![function("same", "pub fn same ()")]);
+        let new = index_of(vec![function("same", "pub fn same ()")]);
+        let diff = diff_indexes(&old, &new);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+    }
+}
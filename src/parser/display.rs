@@ -63,6 +63,7 @@ mod tests {
             doc_comment: doc.to_string(),
             feature_gate: None,
             generics: None,
+            deprecated: None,
             source_file: PathBuf::from("src/lib.rs"),
             line_number: 1,
         }
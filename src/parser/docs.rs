@@ -53,10 +53,91 @@ pub fn extract_doc_comment(attrs: &[Attribute]) -> String {
         .to_string()
 }
 
+/// Extract the note from a `#[deprecated]` attribute, prefixed with the
+/// `since` version when given. `None` if the item isn't deprecated; an empty
+/// string for a bare `#[deprecated]`.
+pub fn extract_deprecation(attrs: &[Attribute]) -> Option<String> {
+    let attr = attrs.iter().find(|a| a.path().is_ident("deprecated"))?;
+    let mut since = None;
+    let mut note = None;
+    match &attr.meta {
+        syn::Meta::NameValue(nv) => {
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit_str),
+                ..
+            }) = &nv.value
+            {
+                note = Some(lit_str.value());
+            }
+        }
+        syn::Meta::List(list) => {
+            let _ = list.parse_nested_meta(|meta| {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("since") {
+                    since = Some(value.value());
+                } else if meta.path.is_ident("note") {
+                    note = Some(value.value());
+                }
+                Ok(())
+            });
+        }
+        syn::Meta::Path(_) => {}
+    }
+    let note = note.unwrap_or_default();
+    Some(match since {
+        Some(since) if note.is_empty() => format!("since {since}"),
+        Some(since) => format!("since {since}: {note}"),
+        None => note,
+    })
+}
+
+/// Extract the contents of fenced code blocks from a doc comment.
+pub fn extract_code_blocks(doc: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut in_block = false;
+    let mut current_block = Vec::new();
+
+    for line in doc.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            if in_block {
+                blocks.push(current_block.join("\n"));
+                current_block.clear();
+                in_block = false;
+            } else {
+                in_block = true;
+            }
+        } else if in_block {
+            current_block.push(line.to_string());
+        }
+    }
+
+    blocks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn deprecation(source: &str) -> Option<String> {
+        let item: syn::ItemFn = syn::parse_str(source).unwrap();
+        extract_deprecation(&item.attrs)
+    }
+
+    #[test]
+    fn extracts_deprecation_notes() {
+        assert_eq!(deprecation("fn a() {}"), None);
+        assert_eq!(deprecation("#[deprecated] fn a() {}"), Some(String::new()));
+        assert_eq!(
+            deprecation("#[deprecated = \"use b\"] fn a() {}"),
+            Some("use b".to_string())
+        );
+        assert_eq!(
+            deprecation("#[deprecated(since = \"0.3\", note = \"use b\")] fn a() {}"),
+            Some("since 0.3: use b".to_string())
+        );
+    }
+
     #[test]
     fn extracts_module_docs_from_source() {
         let source = r#"//! Module documentation
@@ -109,4 +190,18 @@ use std::collections::HashMap;
             assert_eq!(doc, "");
         }
     }
+
+    #[test]
+    fn extracts_code_blocks_from_doc() {
+        let doc = "Example:\n\n```rust\nlet mv = Multivector::new(vec![1.0]);\n```\n\nAnother:\n\n```\nlet x = 42;\n```\n";
+        let blocks = extract_code_blocks(doc);
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[0].contains("Multivector::new"));
+        assert!(blocks[1].contains("let x = 42"));
+    }
+
+    #[test]
+    fn no_code_blocks_returns_empty() {
+        assert!(extract_code_blocks("Just a plain doc.").is_empty());
+    }
 }
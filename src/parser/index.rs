@@ -78,6 +78,8 @@ pub struct ApiItem {
     pub doc_comment: String,
     pub feature_gate: Option<String>,
     pub generics: Option<String>,
+    /// `#[deprecated]` note, see [`crate::parser::docs::extract_deprecation`].
+    pub deprecated: Option<String>,
     pub source_file: PathBuf,
     pub line_number: usize,
 }
@@ -223,19 +225,24 @@ impl ApiIndex<Validated> {
         }
     }
 
-    /// Search items by name substring (case-insensitive).
+    /// Search items by name substring (case-insensitive), sorted by path so
+    /// results and their truncation don't depend on hash order.
     pub fn search(&self, query: &str) -> Vec<&ApiItem> {
         let query_lower = query.to_lowercase();
-        self.items_by_name
+        let mut items: Vec<&ApiItem> = self
+            .items_by_name
             .iter()
             .filter(|(name, _)| name.to_lowercase().contains(&query_lower))
             .flat_map(|(_, items)| items.iter())
-            .collect()
+            .collect();
+        items.sort_by(|a, b| a.full_path.cmp(&b.full_path));
+        items
     }
 
-    /// Get all items gated by a specific feature.
+    /// Get all items gated by a specific feature, sorted by path.
     pub fn feature_items(&self, feature: &str) -> Vec<&ApiItem> {
-        self.items_by_name
+        let mut items: Vec<&ApiItem> = self
+            .items_by_name
             .values()
             .flatten()
             .filter(|item| {
@@ -244,7 +251,9 @@ impl ApiIndex<Validated> {
                     .map(|fg| fg.contains(feature))
                     .unwrap_or(false)
             })
-            .collect()
+            .collect();
+        items.sort_by(|a, b| a.full_path.cmp(&b.full_path));
+        items
     }
 
     /// Get all items in a specific crate (by name or alias).
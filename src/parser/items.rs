@@ -1,4 +1,4 @@
-use crate::parser::docs::extract_doc_comment;
+use crate::parser::docs::{extract_deprecation, extract_doc_comment};
 use crate::parser::features::extract_feature_gate;
 use crate::parser::index::*;
 use quote::ToTokens;
@@ -56,6 +56,7 @@ fn extract_item(
                 doc_comment: extract_doc_comment(&item_fn.attrs),
                 feature_gate,
                 generics: render_generics(&sig.generics),
+                deprecated: extract_deprecation(&item_fn.attrs),
                 source_file: source_path.to_path_buf(),
                 line_number: line_of(sig.ident.span()),
             });
@@ -101,6 +102,7 @@ fn extract_item(
                 doc_comment: extract_doc_comment(&item_struct.attrs),
                 feature_gate,
                 generics: render_generics(&item_struct.generics),
+                deprecated: extract_deprecation(&item_struct.attrs),
                 source_file: source_path.to_path_buf(),
                 line_number: line_of(item_struct.ident.span()),
             });
@@ -161,6 +163,7 @@ fn extract_item(
                 doc_comment: extract_doc_comment(&item_enum.attrs),
                 feature_gate,
                 generics: render_generics(&item_enum.generics),
+                deprecated: extract_deprecation(&item_enum.attrs),
                 source_file: source_path.to_path_buf(),
                 line_number: line_of(item_enum.ident.span()),
             });
@@ -189,6 +192,7 @@ fn extract_item(
                 doc_comment: extract_doc_comment(&item_trait.attrs),
                 feature_gate,
                 generics: render_generics(&item_trait.generics),
+                deprecated: extract_deprecation(&item_trait.attrs),
                 source_file: source_path.to_path_buf(),
                 line_number: line_of(item_trait.ident.span()),
             });
@@ -233,6 +237,7 @@ fn extract_item(
                         doc_comment: extract_doc_comment(&method.attrs),
                         feature_gate: method_feature,
                         generics: render_generics(&sig.generics),
+                        deprecated: extract_deprecation(&method.attrs),
                         source_file: source_path.to_path_buf(),
                         line_number: line_of(sig.ident.span()),
                     });
@@ -257,6 +262,7 @@ fn extract_item(
                 doc_comment: extract_doc_comment(&item_type.attrs),
                 feature_gate,
                 generics: render_generics(&item_type.generics),
+                deprecated: extract_deprecation(&item_type.attrs),
                 source_file: source_path.to_path_buf(),
                 line_number: line_of(item_type.ident.span()),
             });
@@ -285,6 +291,7 @@ fn extract_item(
                 doc_comment: extract_doc_comment(&item_const.attrs),
                 feature_gate,
                 generics: None,
+                deprecated: extract_deprecation(&item_const.attrs),
                 source_file: source_path.to_path_buf(),
                 line_number: line_of(item_const.ident.span()),
             });
@@ -312,6 +319,7 @@ fn extract_item(
                 doc_comment: extract_doc_comment(&item_use.attrs),
                 feature_gate,
                 generics: None,
+                deprecated: extract_deprecation(&item_use.attrs),
                 source_file: source_path.to_path_buf(),
                 line_number: 0,
             });
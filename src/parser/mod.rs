@@ -1,17 +1,32 @@
+pub mod changelog;
+pub mod diff;
 pub mod display;
 pub mod docs;
 pub mod features;
 pub mod index;
 pub mod items;
 pub mod module_tree;
+pub mod sandbox;
+pub mod usages;
 pub mod workspace;
 
 use crate::config::LibraryManifest;
 use anyhow::Result;
 use index::{ApiIndex, CrateInfo, Unvalidated};
 use rayon::prelude::*;
+use sandbox::PathSandbox;
 use std::path::Path;
 
+/// Size rayon's global pool, which `build_index` parses crates on.
+///
+/// Must run before the first index build; rayon initializes the pool once.
+pub fn configure_threads(threads: usize) -> Result<()> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.max(1))
+        .build_global()?;
+    Ok(())
+}
+
 /// Build an API index from the library described by the manifest.
 ///
 /// `manifest_path` is the path to the TOML manifest file, used to resolve
@@ -20,10 +35,25 @@ use std::path::Path;
 pub fn build_index(
     manifest: &LibraryManifest,
     manifest_path: &Path,
+) -> Result<ApiIndex<Unvalidated>> {
+    build_index_with_source(manifest, manifest_path, &manifest.library.source_path)
+}
+
+/// Build an API index like [`build_index`], but read the library from
+/// `source_path` (relative to the manifest) instead of the manifest's own
+/// `source_path`. Used to index older checkouts for API diffing.
+pub fn build_index_with_source(
+    manifest: &LibraryManifest,
+    manifest_path: &Path,
+    source_path: &str,
 ) -> Result<ApiIndex<Unvalidated>> {
     let manifest_path =
         std::fs::canonicalize(manifest_path).unwrap_or_else(|_| manifest_path.to_path_buf());
-    let source_root = manifest.resolve_source_path(&manifest_path);
+    let source_root = manifest_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(source_path);
+    let sandbox = PathSandbox::new(&source_root, &manifest.resolve_allowed_paths());
 
     let resolved_crates = manifest.all_user_facing_crates();
 
@@ -34,6 +64,7 @@ pub fn build_index(
             if !crate_dir.exists() {
                 anyhow::bail!("Crate directory not found: {}", crate_dir.display());
             }
+            sandbox.check(&crate_dir)?;
 
             let lib_path = crate_dir.join("src/lib.rs");
             let module_docs = if lib_path.exists() {
@@ -43,7 +74,7 @@ pub fn build_index(
                 String::new()
             };
 
-            let modules = module_tree::walk_crate(&crate_dir)?;
+            let modules = module_tree::walk_crate_sandboxed(&crate_dir, &sandbox)?;
 
             Ok(CrateInfo {
                 name: resolved.dir_name.clone(),
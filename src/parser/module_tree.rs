@@ -2,12 +2,22 @@ use crate::parser::docs;
 use crate::parser::features;
 use crate::parser::index::ModuleInfo;
 use crate::parser::items;
+use crate::parser::sandbox::PathSandbox;
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 
 /// Walk a crate directory starting from `src/lib.rs` and build
 /// the full module tree with extracted API items.
+///
+/// Module files are confined to the crate directory; use
+/// [`walk_crate_sandboxed`] to permit a wider set of roots.
 pub fn walk_crate(crate_dir: &Path) -> Result<Vec<ModuleInfo>> {
+    walk_crate_sandboxed(crate_dir, &PathSandbox::new(crate_dir, &[]))
+}
+
+/// Walk a crate like [`walk_crate`], skipping any module file that
+/// resolves outside `sandbox`.
+pub fn walk_crate_sandboxed(crate_dir: &Path, sandbox: &PathSandbox) -> Result<Vec<ModuleInfo>> {
     let lib_path = crate_dir.join("src/lib.rs");
     if !lib_path.exists() {
         // Some crates might use src/main.rs or have no src/lib.rs
@@ -30,7 +40,7 @@ pub fn walk_crate(crate_dir: &Path) -> Result<Vec<ModuleInfo>> {
 
     // Discover and recurse into submodules
     let src_dir = crate_dir.join("src");
-    let submodules = discover_submodules(&file, &src_dir, crate_name)?;
+    let submodules = discover_submodules(&file, &src_dir, crate_name, sandbox)?;
 
     // The root module contains lib.rs items plus all submodules
     let root = ModuleInfo {
@@ -50,6 +60,7 @@ fn discover_submodules(
     file: &syn::File,
     src_dir: &Path,
     module_prefix: &str,
+    sandbox: &PathSandbox,
 ) -> Result<Vec<ModuleInfo>> {
     let mut modules = Vec::new();
 
@@ -75,7 +86,7 @@ fn discover_submodules(
                     feature_gate.as_deref(),
                 );
 
-                let submodules = discover_submodules(&inline_file, src_dir, &prefix)?;
+                let submodules = discover_submodules(&inline_file, src_dir, &prefix, sandbox)?;
 
                 modules.push(ModuleInfo {
                     name: mod_name,
@@ -95,6 +106,7 @@ fn discover_submodules(
                         module_prefix,
                         &mod_name,
                         feature_gate.as_deref(),
+                        sandbox,
                     ) {
                         Ok(module_info) => modules.push(module_info),
                         Err(e) => {
@@ -149,7 +161,10 @@ fn parse_module_file(
     parent_prefix: &str,
     mod_name: &str,
     feature_gate: Option<&str>,
+    sandbox: &PathSandbox,
 ) -> Result<ModuleInfo> {
+    sandbox.check(path)?;
+
     let content = std::fs::read_to_string(path)
         .with_context(|| format!("Failed to read module {}", path.display()))?;
 
@@ -169,10 +184,10 @@ fn parse_module_file(
     };
 
     let submodules = if mod_dir.is_dir() {
-        discover_submodules(&file, &mod_dir, &prefix)?
+        discover_submodules(&file, &mod_dir, &prefix, sandbox)?
     } else {
         // Still discover inline modules even if no directory exists
-        discover_submodules(&file, path.parent().unwrap_or(path), &prefix)?
+        discover_submodules(&file, path.parent().unwrap_or(path), &prefix, sandbox)?
     };
 
     Ok(ModuleInfo {
@@ -321,6 +336,31 @@ pub struct Point {
         assert!(modules.is_empty());
     }
 
+    #[test]
+    fn skips_path_attribute_outside_crate() {
+        let tmp = TempDir::new().unwrap();
+        let crate_dir = tmp.path().join("escape-crate");
+        let src = crate_dir.join("src");
+        fs::create_dir_all(&src).unwrap();
+
+        fs::write(tmp.path().join("outside.rs"), "pub fn leaked() {}\n").unwrap();
+        fs::write(
+            src.join("lib.rs"),
+            "#[path = \"../../outside.rs\"]\npub mod outside;\npub fn inside() {}\n",
+        )
+        .unwrap();
+
+        let modules = walk_crate(&crate_dir).unwrap();
+        let root = &modules[0];
+        assert!(root.items.iter().any(|i| i.name == "inside"));
+        assert!(root.submodules.is_empty());
+
+        // The same layout is accepted when the parent directory is allowlisted
+        let sandbox = PathSandbox::new(&crate_dir, &[tmp.path().to_path_buf()]);
+        let modules = walk_crate_sandboxed(&crate_dir, &sandbox).unwrap();
+        assert_eq!(modules[0].submodules.len(), 1);
+    }
+
     #[test]
     fn handles_inline_module() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,94 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+/// Confines source reads to the library root and an allowlist of extra
+/// directories.
+///
+/// Every candidate path is canonicalized before the check, so `..`
+/// components and symlinks can't be used to reach files outside the
+/// permitted roots (e.g. via `#[path = "../../../etc/passwd"]`).
+#[derive(Debug, Clone)]
+pub struct PathSandbox {
+    roots: Vec<PathBuf>,
+}
+
+impl PathSandbox {
+    /// Create a sandbox rooted at `root`, also permitting `extra_roots`.
+    /// Roots that don't exist are dropped.
+    pub fn new(root: &Path, extra_roots: &[PathBuf]) -> Self {
+        let roots = std::iter::once(root)
+            .chain(extra_roots.iter().map(PathBuf::as_path))
+            .filter_map(|path| std::fs::canonicalize(path).ok())
+            .collect();
+        Self { roots }
+    }
+
+    /// Return the canonical form of `path` if it lies within a permitted root.
+    pub fn check(&self, path: &Path) -> Result<PathBuf> {
+        let canonical = std::fs::canonicalize(path)
+            .map_err(|e| anyhow::anyhow!("Cannot resolve {}: {e}", path.display()))?;
+        if self.roots.iter().any(|root| canonical.starts_with(root)) {
+            Ok(canonical)
+        } else {
+            bail!(
+                "{} is outside the permitted source directories",
+                path.display()
+            )
+        }
+    }
+
+    /// Whether `path` lies within a permitted root.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.check(path).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn allows_paths_inside_root() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("src/lib.rs");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "").unwrap();
+
+        let sandbox = PathSandbox::new(tmp.path(), &[]);
+        assert!(sandbox.contains(&file));
+    }
+
+    #[test]
+    fn rejects_traversal_outside_root() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("root");
+        let outside = tmp.path().join("secret.rs");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(&outside, "").unwrap();
+
+        let sandbox = PathSandbox::new(&root, &[]);
+        assert!(!sandbox.contains(&root.join("../secret.rs")));
+    }
+
+    #[test]
+    fn allowlisted_directories_are_permitted() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("root");
+        let shared = tmp.path().join("shared");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(shared.join("common.rs"), "").unwrap();
+
+        let sandbox = PathSandbox::new(&root, std::slice::from_ref(&shared));
+        assert!(sandbox.contains(&root.join("../shared/common.rs")));
+    }
+
+    #[test]
+    fn missing_paths_are_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let sandbox = PathSandbox::new(tmp.path(), &[]);
+        assert!(sandbox.check(&tmp.path().join("missing.rs")).is_err());
+    }
+}
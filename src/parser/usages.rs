@@ -0,0 +1,179 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use syn::visit::Visit;
+use walkdir::WalkDir;
+
+/// A path into one of the library's crates, as written in a user's project.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Usage {
+    /// Path segments, starting with the crate identifier (`amari_core`).
+    pub path: Vec<String>,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+impl Usage {
+    /// The path as written, e.g. `amari_core::rotor::Rotor`.
+    pub fn display_path(&self) -> String {
+        self.path.join("::")
+    }
+}
+
+/// Usages found in a project, plus files that couldn't be read or parsed.
+#[derive(Debug, Default)]
+pub struct ProjectScan {
+    pub files_scanned: usize,
+    pub usages: Vec<Usage>,
+    pub errors: Vec<String>,
+}
+
+/// Find `use` declarations and qualified paths naming any of `crates`
+/// (identifiers, e.g. `amari_core`) in the `.rs` files under `root`.
+/// `target/` and hidden directories are skipped.
+pub fn scan_project(root: &Path, crates: &[String]) -> ProjectScan {
+    let mut scan = ProjectScan::default();
+    let files = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0 || !(name.starts_with('.') || name == "target")
+        })
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"));
+
+    for entry in files {
+        let path = entry.path();
+        let parsed = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| syn::parse_file(&source).map_err(|e| e.to_string()));
+        let file = match parsed {
+            Ok(file) => file,
+            Err(e) => {
+                scan.errors.push(format!("{}: {e}", path.display()));
+                continue;
+            }
+        };
+        scan.files_scanned += 1;
+
+        let mut visitor = UsageVisitor {
+            crates,
+            found: BTreeSet::new(),
+        };
+        visitor.visit_file(&file);
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        scan.usages
+            .extend(visitor.found.into_iter().map(|(line, path)| Usage {
+                path,
+                file: relative.to_path_buf(),
+                line,
+            }));
+    }
+    scan
+}
+
+struct UsageVisitor<'a> {
+    crates: &'a [String],
+    found: BTreeSet<(usize, Vec<String>)>,
+}
+
+impl UsageVisitor<'_> {
+    fn record(&mut self, path: Vec<String>, line: usize) {
+        if path.len() >= 2 && self.crates.contains(&path[0]) {
+            self.found.insert((line, path));
+        }
+    }
+
+    fn use_tree(&mut self, tree: &syn::UseTree, prefix: &mut Vec<String>) {
+        match tree {
+            syn::UseTree::Path(p) => {
+                prefix.push(p.ident.to_string());
+                self.use_tree(&p.tree, prefix);
+                prefix.pop();
+            }
+            syn::UseTree::Name(name) => {
+                let line = name.ident.span().start().line;
+                let mut path = prefix.clone();
+                if name.ident != "self" {
+                    path.push(name.ident.to_string());
+                }
+                self.record(path, line);
+            }
+            syn::UseTree::Rename(rename) => {
+                let line = rename.ident.span().start().line;
+                let mut path = prefix.clone();
+                if rename.ident != "self" {
+                    path.push(rename.ident.to_string());
+                }
+                self.record(path, line);
+            }
+            syn::UseTree::Glob(glob) => {
+                let line = glob.star_token.spans[0].start().line;
+                self.record(prefix.clone(), line);
+            }
+            syn::UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.use_tree(tree, prefix);
+                }
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for UsageVisitor<'_> {
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        self.use_tree(&item.tree, &mut Vec::new());
+    }
+
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if let Some(first) = path.segments.first() {
+            let segments = path.segments.iter().map(|s| s.ident.to_string()).collect();
+            self.record(segments, first.ident.span().start().line);
+        }
+        syn::visit::visit_path(self, path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(source: &str) -> Vec<String> {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), source).unwrap();
+        let scan = scan_project(dir.path(), &["mylib_core".to_string()]);
+        assert_eq!(scan.files_scanned, 1);
+        scan.usages.iter().map(Usage::display_path).collect()
+    }
+
+    #[test]
+    fn finds_use_trees_and_qualified_paths() {
+        let usages = scan(
+            "use mylib_core::{shapes::{self, Point}, Area as A};\n\
+             use std::fmt;\n\
+             fn main() { let p = mylib_core::shapes::Circle::new(1.0); }\n",
+        );
+        assert_eq!(
+            usages,
+            vec![
+                "mylib_core::Area",
+                "mylib_core::shapes",
+                "mylib_core::shapes::Point",
+                "mylib_core::shapes::Circle::new",
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_target_and_reports_unparsable_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/gen.rs"), "use mylib_core::X;").unwrap();
+        std::fs::write(dir.path().join("broken.rs"), "fn {").unwrap();
+        let scan = scan_project(dir.path(), &["mylib_core".to_string()]);
+        assert_eq!(scan.files_scanned, 0);
+        assert!(scan.usages.is_empty());
+        assert_eq!(scan.errors.len(), 1);
+    }
+}
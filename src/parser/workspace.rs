@@ -1,5 +1,6 @@
+use crate::config::LibraryManifest;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
 
 /// Parsed dependency information from a workspace member's Cargo.toml.
@@ -51,6 +52,304 @@ pub fn parse_crate_cargo_toml(crate_dir: &Path) -> Result<CrateDeps> {
     Ok(CrateDeps { name, sibling_deps })
 }
 
+/// Version and feature names declared in the workspace root Cargo.toml.
+#[derive(Debug, Default)]
+pub struct WorkspaceMetadata {
+    /// The umbrella crate's `[package] name`.
+    pub package: Option<String>,
+    pub version: Option<String>,
+    pub features: Vec<String>,
+    /// Each feature's entries as written (`"dep:x"`, `"x/feat"`, `"other"`).
+    pub feature_table: BTreeMap<String, Vec<String>>,
+}
+
+/// Read the library version (`[workspace.package]` or `[package]`) and the
+/// umbrella crate's `[features]` from the workspace root Cargo.toml.
+pub fn read_workspace_metadata(cargo_toml: &Path) -> Result<WorkspaceMetadata> {
+    let content = std::fs::read_to_string(cargo_toml)
+        .with_context(|| format!("Failed to read {}", cargo_toml.display()))?;
+    let parsed: toml::Value = content
+        .parse()
+        .with_context(|| format!("Failed to parse {}", cargo_toml.display()))?;
+
+    let version = parsed
+        .get("workspace")
+        .and_then(|w| w.get("package"))
+        .and_then(|p| p.get("version"))
+        .or_else(|| parsed.get("package").and_then(|p| p.get("version")))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    let package = parsed
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(String::from);
+
+    let feature_table: BTreeMap<String, Vec<String>> = parsed
+        .get("features")
+        .and_then(|f| f.as_table())
+        .map(|table| {
+            table
+                .iter()
+                .map(|(name, entries)| {
+                    let entries = entries
+                        .as_array()
+                        .map(|a| {
+                            a.iter()
+                                .filter_map(|e| e.as_str().map(String::from))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    (name.clone(), entries)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let features = feature_table.keys().cloned().collect();
+
+    Ok(WorkspaceMetadata {
+        package,
+        version,
+        features,
+        feature_table,
+    })
+}
+
+impl WorkspaceMetadata {
+    /// Features transitively enabled by `feature` (including itself) and the
+    /// optional dependencies they activate. Weak `dep?/feat` entries don't
+    /// activate their dependency and are skipped.
+    pub fn expand_feature(&self, feature: &str) -> (BTreeSet<String>, BTreeSet<String>) {
+        let mut features = BTreeSet::new();
+        let mut deps = BTreeSet::new();
+        let mut pending = vec![feature.to_string()];
+        while let Some(name) = pending.pop() {
+            if !features.insert(name.clone()) {
+                continue;
+            }
+            for entry in self.feature_table.get(&name).into_iter().flatten() {
+                if let Some(dep) = entry.strip_prefix("dep:") {
+                    deps.insert(dep.to_string());
+                } else if let Some((dep, _)) = entry.split_once('/') {
+                    if !dep.ends_with('?') {
+                        deps.insert(dep.to_string());
+                    }
+                } else if self.feature_table.contains_key(entry) {
+                    pending.push(entry.clone());
+                } else {
+                    // An optional dependency used as an implicit feature.
+                    deps.insert(entry.clone());
+                }
+            }
+        }
+        (features, deps)
+    }
+}
+
+/// How a list of desired capabilities maps onto the umbrella crate's
+/// declared features.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FeatureResolution {
+    pub package: String,
+    pub version: String,
+    pub default_features: bool,
+    pub resolved: Vec<ResolvedCapability>,
+    pub unresolved: Vec<UnresolvedCapability>,
+    /// Distinct features to enable, sorted.
+    pub features: Vec<String>,
+    /// A `[dependencies]` entry enabling `features`.
+    pub stanza: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ResolvedCapability {
+    pub capability: String,
+    /// The declared feature chosen; `None` when the capability only turns
+    /// default features off (`no_std`).
+    pub feature: Option<String>,
+    /// Other features it turns on transitively.
+    pub implies: Vec<String>,
+    /// Optional dependencies it activates.
+    pub crates: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnresolvedCapability {
+    pub capability: String,
+    /// Declared features that partly match, or all of them when none do.
+    pub candidates: Vec<String>,
+}
+
+/// Map capability names (`gpu`, `no_std`, `high-precision`) to features
+/// declared in `metadata`, never inventing a feature that isn't there.
+///
+/// A capability matches a feature with the same name (ignoring case and
+/// `_`/`-`), then an `optional` feature (feature → crate directory) whose
+/// crate or `aliases` entry matches, then a single feature whose name
+/// contains it or is contained in it. `no_std` resolves to
+/// `default-features = false` when `std` is a default feature.
+pub fn resolve_capabilities(
+    metadata: &WorkspaceMetadata,
+    package: &str,
+    version: &str,
+    optional: &HashMap<String, String>,
+    aliases: &HashMap<String, String>,
+    capabilities: &[String],
+) -> FeatureResolution {
+    let declared: Vec<&String> = metadata
+        .feature_table
+        .keys()
+        .filter(|f| f.as_str() != "default")
+        .collect();
+
+    let mut resolution = FeatureResolution {
+        package: metadata
+            .package
+            .clone()
+            .unwrap_or_else(|| package.to_string()),
+        version: metadata
+            .version
+            .clone()
+            .unwrap_or_else(|| version.to_string()),
+        default_features: true,
+        ..Default::default()
+    };
+
+    for capability in capabilities {
+        let wanted = normalize(capability);
+        if wanted == "no-std" {
+            let (defaults, _) = metadata.expand_feature("default");
+            if defaults.contains("std") {
+                resolution.default_features = false;
+                resolution.resolved.push(ResolvedCapability {
+                    capability: capability.clone(),
+                    feature: None,
+                    implies: Vec::new(),
+                    crates: Vec::new(),
+                });
+                continue;
+            }
+        }
+
+        let exact = declared.iter().find(|f| normalize(f) == wanted);
+        let by_crate = || {
+            declared.iter().find(|f| {
+                optional.get(f.as_str()).is_some_and(|dir| {
+                    normalize(dir) == wanted
+                        || normalize(dir).ends_with(&format!("-{wanted}"))
+                        || aliases.get(dir).is_some_and(|a| normalize(a) == wanted)
+                })
+            })
+        };
+        let partial: Vec<&String> = declared
+            .iter()
+            .filter(|f| {
+                let name = normalize(f);
+                name.contains(&wanted) || wanted.contains(&name)
+            })
+            .copied()
+            .collect();
+
+        let chosen = exact
+            .or_else(by_crate)
+            .copied()
+            .or_else(|| (partial.len() == 1).then(|| partial[0]));
+        let Some(feature) = chosen else {
+            let candidates = if partial.is_empty() {
+                declared.clone()
+            } else {
+                partial
+            };
+            resolution.unresolved.push(UnresolvedCapability {
+                capability: capability.clone(),
+                candidates: candidates.into_iter().cloned().collect(),
+            });
+            continue;
+        };
+
+        let (features, crates) = metadata.expand_feature(feature);
+        resolution.resolved.push(ResolvedCapability {
+            capability: capability.clone(),
+            feature: Some(feature.clone()),
+            implies: features.into_iter().filter(|f| f != feature).collect(),
+            crates: crates.into_iter().collect(),
+        });
+    }
+
+    let features: BTreeSet<&String> = resolution
+        .resolved
+        .iter()
+        .filter_map(|r| r.feature.as_ref())
+        .collect();
+    resolution.features = features.into_iter().cloned().collect();
+    resolution.stanza = dependency_stanza(
+        &resolution.package,
+        &resolution.version,
+        resolution.default_features,
+        &resolution.features,
+    );
+    resolution
+}
+
+/// Lowercase with `_` as `-`, so `no_std` matches `no-std`.
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace('_', "-")
+}
+
+fn dependency_stanza(
+    package: &str,
+    version: &str,
+    default_features: bool,
+    features: &[String],
+) -> String {
+    if default_features && features.is_empty() {
+        return format!("[dependencies]\n{package} = \"{version}\"\n");
+    }
+    let mut fields = vec![format!("version = \"{version}\"")];
+    if !default_features {
+        fields.push("default-features = false".to_string());
+    }
+    if !features.is_empty() {
+        let quoted: Vec<String> = features.iter().map(|f| format!("\"{f}\"")).collect();
+        fields.push(format!("features = [{}]", quoted.join(", ")));
+    }
+    format!("[dependencies]\n{package} = {{ {} }}\n", fields.join(", "))
+}
+
+/// Compare the manifest's hand-written version and optional features with
+/// the workspace's Cargo metadata, returning one message per mismatch.
+pub fn manifest_drift(manifest: &LibraryManifest, metadata: &WorkspaceMetadata) -> Vec<String> {
+    let mut drift = Vec::new();
+
+    if let Some(version) = &metadata.version {
+        if *version != manifest.library.version {
+            drift.push(format!(
+                "manifest version {} differs from workspace version {version}",
+                manifest.library.version
+            ));
+        }
+    }
+
+    if !metadata.features.is_empty() {
+        let mut missing: Vec<&str> = manifest
+            .crates
+            .optional
+            .keys()
+            .filter(|feature| !metadata.features.contains(feature))
+            .map(String::as_str)
+            .collect();
+        missing.sort_unstable();
+        for feature in missing {
+            drift.push(format!(
+                "manifest feature '{feature}' is not declared in the workspace [features]"
+            ));
+        }
+    }
+
+    drift
+}
+
 /// Build a dependency graph for all crates in the workspace.
 /// Returns a map from crate name to its sibling dependencies.
 pub fn build_dependency_graph(crate_dirs: &[(String, &Path)]) -> HashMap<String, Vec<String>> {
@@ -115,6 +414,163 @@ version = "0.1.0"
         assert!(deps.sibling_deps.is_empty());
     }
 
+    #[test]
+    fn reads_workspace_version_and_features() {
+        let tmp = TempDir::new().unwrap();
+        let cargo_path = tmp.path().join("Cargo.toml");
+        fs::write(
+            &cargo_path,
+            r#"
+[workspace.package]
+version = "0.18.1"
+
+[package]
+name = "umbrella"
+version.workspace = true
+
+[features]
+default = []
+gpu = ["dep:umbrella-gpu"]
+"#,
+        )
+        .unwrap();
+
+        let metadata = read_workspace_metadata(&cargo_path).unwrap();
+        assert_eq!(metadata.version.as_deref(), Some("0.18.1"));
+        assert_eq!(metadata.features, vec!["default", "gpu"]);
+        assert_eq!(metadata.package.as_deref(), Some("umbrella"));
+        assert_eq!(metadata.feature_table["gpu"], vec!["dep:umbrella-gpu"]);
+    }
+
+    fn umbrella_metadata() -> WorkspaceMetadata {
+        let table: toml::Table = toml::from_str(
+            r#"
+default = ["std"]
+std = []
+gpu = ["dep:umbrella-gpu", "std"]
+wasm = ["umbrella-core/wasm"]
+high-precision = ["umbrella-core?/rug"]
+precision-f32 = []
+"#,
+        )
+        .unwrap();
+        let feature_table: BTreeMap<String, Vec<String>> = table
+            .into_iter()
+            .map(|(name, entries)| {
+                let entries = entries
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|e| e.as_str().unwrap().to_string())
+                    .collect();
+                (name, entries)
+            })
+            .collect();
+        WorkspaceMetadata {
+            package: Some("umbrella".to_string()),
+            version: Some("0.3.0".to_string()),
+            features: feature_table.keys().cloned().collect(),
+            feature_table,
+        }
+    }
+
+    #[test]
+    fn expands_features_transitively() {
+        let (features, deps) = umbrella_metadata().expand_feature("gpu");
+        assert_eq!(features.into_iter().collect::<Vec<_>>(), vec!["gpu", "std"]);
+        assert_eq!(deps.into_iter().collect::<Vec<_>>(), vec!["umbrella-gpu"]);
+
+        let (_, deps) = umbrella_metadata().expand_feature("high-precision");
+        assert!(deps.is_empty());
+    }
+
+    #[test]
+    fn resolves_capabilities_against_declared_features() {
+        let optional = HashMap::from([("gpu".to_string(), "umbrella-gpu".to_string())]);
+        let aliases = HashMap::from([("umbrella-gpu".to_string(), "accel".to_string())]);
+        let capabilities: Vec<String> = ["accel", "WASM", "no_std", "precision", "quantum"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let resolution = resolve_capabilities(
+            &umbrella_metadata(),
+            "fallback",
+            "9.9.9",
+            &optional,
+            &aliases,
+            &capabilities,
+        );
+
+        assert_eq!(resolution.package, "umbrella");
+        assert!(!resolution.default_features);
+        assert_eq!(resolution.features, vec!["gpu", "wasm"]);
+        assert_eq!(resolution.resolved[0].crates, vec!["umbrella-gpu"]);
+        assert_eq!(resolution.resolved[0].implies, vec!["std"]);
+        assert_eq!(resolution.resolved[2].feature, None);
+
+        let unresolved: Vec<&str> = resolution
+            .unresolved
+            .iter()
+            .map(|u| u.capability.as_str())
+            .collect();
+        assert_eq!(unresolved, vec!["precision", "quantum"]);
+        assert_eq!(
+            resolution.unresolved[0].candidates,
+            vec!["high-precision", "precision-f32"]
+        );
+        assert_eq!(resolution.unresolved[1].candidates.len(), 5);
+        assert_eq!(
+            resolution.stanza,
+            "[dependencies]\numbrella = { version = \"0.3.0\", default-features = false, features = [\"gpu\", \"wasm\"] }\n"
+        );
+    }
+
+    #[test]
+    fn plain_stanza_without_features() {
+        assert_eq!(
+            dependency_stanza("umbrella", "0.3.0", true, &[]),
+            "[dependencies]\numbrella = \"0.3.0\"\n"
+        );
+    }
+
+    #[test]
+    fn reports_version_and_feature_drift() {
+        let manifest: LibraryManifest = toml::from_str(
+            r#"
+[library]
+name = "testlib"
+display_name = "Test Library"
+version = "1.0.0"
+description = "A test library"
+source_path = "."
+
+[workspace]
+root_cargo_toml = "Cargo.toml"
+umbrella_crate = "src/lib.rs"
+
+[crates.default]
+members = []
+
+[crates.optional]
+gpu = "testlib-gpu"
+extra = "testlib-extra"
+
+[aliases]
+"#,
+        )
+        .unwrap();
+        let metadata = WorkspaceMetadata {
+            version: Some("1.1.0".to_string()),
+            features: vec!["gpu".to_string()],
+            ..Default::default()
+        };
+
+        let drift = manifest_drift(&manifest, &metadata);
+        assert_eq!(drift.len(), 2);
+        assert!(drift[0].contains("1.1.0"));
+        assert!(drift[1].contains("'extra'"));
+    }
+
     #[test]
     fn builds_dependency_graph() {
         let tmp = TempDir::new().unwrap();
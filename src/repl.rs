@@ -0,0 +1,172 @@
+//! Interactive prompt for calling tools without an MCP client.
+//!
+//! Each line is `tool_name {json}`; the arguments default to `{}`. Tool
+//! names may be abbreviated to any unambiguous prefix.
+
+use crate::tools::registry::ToolRegistry;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::io::Write;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+const HELP: &str = "\
+Commands:
+  <tool> [json]   Call a tool, e.g. api_search {\"query\": \"Rotor\"}
+  tools           List registered tools
+  help            Show this message
+  quit            Exit (also Ctrl-D)
+Tool names may be abbreviated to any unambiguous prefix.";
+
+/// A parsed REPL input line.
+#[derive(Debug, PartialEq)]
+pub enum ReplCommand {
+    Call { tool: String, arguments: Value },
+    Tools,
+    Help,
+    Quit,
+    Empty,
+}
+
+/// Parse one input line, resolving abbreviated tool names against `names`.
+pub fn parse_line(line: &str, names: &[&str]) -> Result<ReplCommand, String> {
+    let line = line.trim();
+    let (word, rest) = match line.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (line, ""),
+    };
+
+    match word {
+        "" => return Ok(ReplCommand::Empty),
+        "tools" if rest.is_empty() => return Ok(ReplCommand::Tools),
+        "help" | "?" if rest.is_empty() => return Ok(ReplCommand::Help),
+        "quit" | "exit" if rest.is_empty() => return Ok(ReplCommand::Quit),
+        _ => {}
+    }
+
+    let tool = complete(word, names)?;
+    let arguments = if rest.is_empty() {
+        json!({})
+    } else {
+        serde_json::from_str(rest).map_err(|e| format!("Invalid JSON arguments: {e}"))?
+    };
+    if !arguments.is_object() {
+        return Err("Arguments must be a JSON object".to_string());
+    }
+
+    Ok(ReplCommand::Call {
+        tool: tool.to_string(),
+        arguments,
+    })
+}
+
+/// Expand `prefix` to the tool it names: an exact match, or the only tool
+/// starting with it.
+pub fn complete<'a>(prefix: &str, names: &[&'a str]) -> Result<&'a str, String> {
+    if let Some(exact) = names.iter().find(|name| **name == prefix) {
+        return Ok(exact);
+    }
+    let matches: Vec<&str> = names
+        .iter()
+        .copied()
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+    match matches.as_slice() {
+        [only] => Ok(only),
+        [] => Err(format!("Unknown tool '{prefix}' (type `tools` to list)")),
+        many => Err(format!("'{prefix}' is ambiguous: {}", many.join(", "))),
+    }
+}
+
+/// Read commands from stdin until EOF or `quit`, printing results to stdout.
+pub async fn run(tools: &ToolRegistry, library_name: &str) -> Result<()> {
+    let names = tools.names();
+    println!(
+        "{library_name} API reference REPL — {} tools. Type `help` for usage.",
+        names.len()
+    );
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        print!("{library_name}> ");
+        std::io::stdout().flush()?;
+
+        let Some(line) = lines.next_line().await? else {
+            println!();
+            break;
+        };
+
+        match parse_line(&line, &names) {
+            Ok(ReplCommand::Empty) => {}
+            Ok(ReplCommand::Help) => println!("{HELP}"),
+            Ok(ReplCommand::Tools) => {
+                for name in &names {
+                    println!("  {name}");
+                }
+            }
+            Ok(ReplCommand::Quit) => break,
+            Ok(ReplCommand::Call { tool, arguments }) => {
+                match tools.call_local(&tool, arguments).await {
+                    Ok(result) => println!("{}", serde_json::to_string_pretty(&result)?),
+                    Err(e) => eprintln!("error: {e}"),
+                }
+            }
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: &[&str] = &["api_search", "api_diff", "type_info", "batch_call"];
+
+    #[test]
+    fn parses_tool_call_with_arguments() {
+        let command = parse_line(r#"api_search {"query": "Rotor"}"#, NAMES).unwrap();
+        assert_eq!(
+            command,
+            ReplCommand::Call {
+                tool: "api_search".to_string(),
+                arguments: json!({"query": "Rotor"}),
+            }
+        );
+    }
+
+    #[test]
+    fn arguments_default_to_empty_object() {
+        let command = parse_line("type_info", NAMES).unwrap();
+        assert_eq!(
+            command,
+            ReplCommand::Call {
+                tool: "type_info".to_string(),
+                arguments: json!({}),
+            }
+        );
+    }
+
+    #[test]
+    fn expands_unambiguous_prefixes() {
+        assert_eq!(complete("ty", NAMES), Ok("type_info"));
+        assert_eq!(complete("api_s", NAMES), Ok("api_search"));
+        let err = complete("api", NAMES).unwrap_err();
+        assert!(err.contains("ambiguous"));
+        assert!(complete("nope", NAMES).is_err());
+    }
+
+    #[test]
+    fn recognizes_builtin_commands() {
+        assert_eq!(parse_line("  ", NAMES), Ok(ReplCommand::Empty));
+        assert_eq!(parse_line("tools", NAMES), Ok(ReplCommand::Tools));
+        assert_eq!(parse_line("help", NAMES), Ok(ReplCommand::Help));
+        assert_eq!(parse_line("quit", NAMES), Ok(ReplCommand::Quit));
+    }
+
+    #[test]
+    fn rejects_malformed_arguments() {
+        assert!(parse_line("type_info {not json", NAMES).is_err());
+        assert!(parse_line("type_info [1, 2]", NAMES).is_err());
+    }
+}
@@ -0,0 +1,428 @@
+//! The indexed crates' source files as MCP resources.
+//!
+//! Each file is addressed as `<library>://source/<crate>/<path>`, e.g.
+//! `amari://source/amari-core/src/lib.rs`, so clients can browse the
+//! checkout with `resources/list` and `resources/read` next to the tools.
+//! Reads are confined to the crate directories by a [`PathSandbox`].
+//!
+//! A read may select part of a file with a `lines=START-END` (1-based,
+//! inclusive) or `bytes=START-END` (0-based, end-exclusive) query. The
+//! returned URI names the range served and carries `hash=<hex>`, a hash of
+//! the whole file, so clients can tell whether cached parts are still
+//! current before fetching more.
+
+use crate::parser::sandbox::PathSandbox;
+use crate::tools::SharedState;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+use walkdir::WalkDir;
+
+/// Resources returned per `resources/list` page.
+pub const PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Error)]
+pub enum ResourceError {
+    #[error("Resource URI must start with '{0}'")]
+    UnknownScheme(String),
+    #[error("Crate '{0}' is not indexed")]
+    UnknownCrate(String),
+    #[error("Invalid path '{0}'")]
+    InvalidPath(String),
+    #[error("Cannot read '{uri}': {reason}")]
+    Unreadable { uri: String, reason: String },
+    #[error("'{uri}' is {size} bytes, maximum is {max}; request a lines= range")]
+    TooLarge { uri: String, size: u64, max: usize },
+    #[error("Invalid cursor '{0}'")]
+    InvalidCursor(String),
+    #[error("Invalid range '{0}'; use lines=START-END or bytes=START-END")]
+    InvalidRange(String),
+}
+
+/// Part of a file selected by a URI query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Range {
+    /// 1-based, inclusive line numbers.
+    Lines(usize, usize),
+    /// 0-based byte offsets, end-exclusive.
+    Bytes(usize, usize),
+}
+
+impl Range {
+    fn parse(query: &str) -> Result<Option<Self>, ResourceError> {
+        let invalid = || ResourceError::InvalidRange(query.to_string());
+        let mut range = None;
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(invalid)?;
+            let bounds = || -> Option<(usize, usize)> {
+                let (start, end) = value.split_once('-')?;
+                Some((start.parse().ok()?, end.parse().ok()?))
+            };
+            range = match key {
+                "lines" => match bounds() {
+                    // `N-(N-1)` is the empty range returned for reads past the end.
+                    Some((start, end)) if start >= 1 && start - 1 <= end => {
+                        Some(Range::Lines(start, end))
+                    }
+                    _ => return Err(invalid()),
+                },
+                "bytes" => match bounds() {
+                    Some((start, end)) if start <= end => Some(Range::Bytes(start, end)),
+                    _ => return Err(invalid()),
+                },
+                // Clients echo back URIs we returned; the hash is informational.
+                "hash" => range,
+                _ => return Err(invalid()),
+            };
+        }
+        Ok(range)
+    }
+
+    /// The selected part of `text`, with the range clamped to its length.
+    fn slice(self, text: &str) -> Result<(Self, &str), ResourceError> {
+        match self {
+            Range::Lines(start, end) => {
+                let lines: Vec<&str> = text.split_inclusive('\n').collect();
+                let end = end.min(lines.len());
+                if start > end {
+                    return Ok((Range::Lines(start, start - 1), ""));
+                }
+                let offset: usize = lines[..start - 1].iter().map(|l| l.len()).sum();
+                let len: usize = lines[start - 1..end].iter().map(|l| l.len()).sum();
+                Ok((Range::Lines(start, end), &text[offset..offset + len]))
+            }
+            Range::Bytes(start, end) => {
+                let end = end.min(text.len());
+                let start = start.min(end);
+                let part = text
+                    .get(start..end)
+                    .ok_or_else(|| ResourceError::InvalidRange(self.to_string()))?;
+                Ok((Range::Bytes(start, end), part))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Range::Lines(start, end) => write!(f, "lines={start}-{end}"),
+            Range::Bytes(start, end) => write!(f, "bytes={start}-{end}"),
+        }
+    }
+}
+
+/// Stable 64-bit FNV-1a hash of `text`, as 16 hex digits.
+pub fn content_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// One listed source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceEntry {
+    pub uri: String,
+    /// Path relative to the crate, e.g. `src/lib.rs`.
+    pub name: String,
+    pub mime_type: &'static str,
+}
+
+/// The contents of one source file, or the requested part of it.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    /// The file's URI with the range served and the whole-file hash.
+    pub uri: String,
+    pub mime_type: &'static str,
+    pub text: String,
+    /// The range served, clamped to the file; `None` for the whole file.
+    pub range: Option<Range>,
+    /// [`content_hash`] of the whole file.
+    pub hash: String,
+}
+
+struct CrateRoot {
+    name: String,
+    dir: PathBuf,
+    sandbox: PathSandbox,
+}
+
+/// Lists and reads source files of the indexed crates.
+pub struct SourceResources {
+    prefix: String,
+    crates: Vec<CrateRoot>,
+    max_bytes: usize,
+}
+
+impl SourceResources {
+    pub fn new(state: &SharedState) -> Self {
+        Self {
+            prefix: format!("{}://source/", state.manifest.library.name),
+            crates: state
+                .index
+                .crates
+                .iter()
+                .map(|c| CrateRoot {
+                    name: c.name.clone(),
+                    dir: c.source_dir.clone(),
+                    sandbox: PathSandbox::new(&c.source_dir, &[]),
+                })
+                .collect(),
+            max_bytes: state.manifest.server.limits.max_response_bytes,
+        }
+    }
+
+    /// `Cargo.toml` and every `.rs` file under `src/` of each crate, sorted
+    /// per crate, paginated by an opaque cursor.
+    pub fn list(
+        &self,
+        cursor: Option<&str>,
+    ) -> Result<(Vec<SourceEntry>, Option<String>), ResourceError> {
+        let start = match cursor {
+            Some(cursor) => cursor
+                .parse::<usize>()
+                .map_err(|_| ResourceError::InvalidCursor(cursor.to_string()))?,
+            None => 0,
+        };
+
+        let entries: Vec<SourceEntry> = self
+            .crates
+            .iter()
+            .flat_map(|root| self.crate_files(root))
+            .map(|(entry, _)| entry)
+            .skip(start)
+            .take(PAGE_SIZE + 1)
+            .collect();
+
+        let mut page = entries;
+        let next = (page.len() > PAGE_SIZE).then(|| {
+            page.truncate(PAGE_SIZE);
+            (start + PAGE_SIZE).to_string()
+        });
+        Ok((page, next))
+    }
+
+    /// Every listed file's URI and path on disk, in listing order.
+    pub fn files(&self) -> Vec<(String, PathBuf)> {
+        self.crates
+            .iter()
+            .flat_map(|root| self.crate_files(root))
+            .map(|(entry, path)| (entry.uri, path))
+            .collect()
+    }
+
+    fn crate_files(&self, root: &CrateRoot) -> Vec<(SourceEntry, PathBuf)> {
+        let mut paths: Vec<PathBuf> = WalkDir::new(root.dir.join("src"))
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "rs"))
+            .collect();
+        paths.sort();
+
+        let manifest = root.dir.join("Cargo.toml");
+        manifest
+            .is_file()
+            .then_some(manifest)
+            .into_iter()
+            .chain(paths)
+            .filter_map(|path| {
+                let relative = path.strip_prefix(&root.dir).ok()?;
+                let name = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let entry = SourceEntry {
+                    uri: format!("{}{}/{name}", self.prefix, root.name),
+                    mime_type: mime_type(&path),
+                    name,
+                };
+                Some((entry, path))
+            })
+            .collect()
+    }
+
+    /// Read the file a `source` URI points at, or the range its query selects.
+    pub fn read(&self, uri: &str) -> Result<SourceFile, ResourceError> {
+        let (base, query) = uri.split_once('?').unwrap_or((uri, ""));
+        let range = Range::parse(query)?;
+        let (root, path) = self.resolve(base)?;
+        let unreadable = |reason: String| ResourceError::Unreadable {
+            uri: uri.to_string(),
+            reason,
+        };
+
+        let path = root
+            .sandbox
+            .check(&path)
+            .map_err(|e| unreadable(e.to_string()))?;
+        let text = std::fs::read_to_string(&path).map_err(|e| unreadable(e.to_string()))?;
+        let hash = content_hash(&text);
+
+        let (range, part) = match range {
+            Some(range) => {
+                let (range, part) = range.slice(&text)?;
+                (Some(range), part)
+            }
+            None => (None, text.as_str()),
+        };
+        if part.len() > self.max_bytes {
+            return Err(ResourceError::TooLarge {
+                uri: uri.to_string(),
+                size: part.len() as u64,
+                max: self.max_bytes,
+            });
+        }
+
+        let uri = match range {
+            Some(range) => format!("{base}?{range}&hash={hash}"),
+            None => format!("{base}?hash={hash}"),
+        };
+        Ok(SourceFile {
+            uri,
+            mime_type: mime_type(&path),
+            text: part.to_string(),
+            range,
+            hash,
+        })
+    }
+
+    /// Split a URI into its crate and a path inside that crate's directory.
+    fn resolve(&self, uri: &str) -> Result<(&CrateRoot, PathBuf), ResourceError> {
+        let rest = uri
+            .strip_prefix(&self.prefix)
+            .ok_or_else(|| ResourceError::UnknownScheme(self.prefix.clone()))?;
+        let (crate_name, relative) = rest
+            .split_once('/')
+            .ok_or_else(|| ResourceError::InvalidPath(rest.to_string()))?;
+        let root = self
+            .crates
+            .iter()
+            .find(|c| c.name == crate_name)
+            .ok_or_else(|| ResourceError::UnknownCrate(crate_name.to_string()))?;
+
+        let relative = Path::new(relative);
+        let plain = relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if relative.as_os_str().is_empty() || !plain {
+            return Err(ResourceError::InvalidPath(
+                relative.to_string_lossy().into_owned(),
+            ));
+        }
+        Ok((root, root.dir.join(relative)))
+    }
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("rs") => "text/x-rust",
+        Some("toml") => "application/toml",
+        _ => "text/plain",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LibraryManifest;
+
+    fn resources() -> SourceResources {
+        let manifest_path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/minilib.toml");
+        let manifest = LibraryManifest::load(&manifest_path).unwrap();
+        let index = crate::parser::build_index(&manifest, &manifest_path)
+            .unwrap()
+            .validate()
+            .unwrap();
+        SourceResources::new(&SharedState::new(index, manifest))
+    }
+
+    #[test]
+    fn lists_manifest_and_sources_per_crate() {
+        let (entries, next) = resources().list(None).unwrap();
+        assert!(next.is_none());
+        let uris: Vec<&str> = entries.iter().map(|e| e.uri.as_str()).collect();
+        assert!(uris.contains(&"minilib://source/minilib-core/Cargo.toml"));
+        assert!(uris.contains(&"minilib://source/minilib-core/src/shapes.rs"));
+        assert!(entries.iter().any(|e| e.name == "src/lib.rs"));
+    }
+
+    #[test]
+    fn reads_listed_files() {
+        let file = resources()
+            .read("minilib://source/minilib-core/src/lib.rs")
+            .unwrap();
+        assert_eq!(file.mime_type, "text/x-rust");
+        assert!(file.text.contains("pub struct Point"));
+    }
+
+    #[test]
+    fn reads_line_and_byte_ranges() {
+        let resources = resources();
+        let base = "minilib://source/minilib-core/src/lib.rs";
+        let whole = resources.read(base).unwrap();
+        assert_eq!(whole.uri, format!("{base}?hash={}", whole.hash));
+
+        let first = resources.read(&format!("{base}?lines=1-1")).unwrap();
+        assert_eq!(first.text, "//! Core types for the minilib fixture.\n");
+        assert_eq!(first.hash, whole.hash);
+        assert!(first.uri.contains("?lines=1-1&hash="));
+
+        let clamped = resources.read(&format!("{base}?lines=3-100000")).unwrap();
+        assert!(whole.text.ends_with(&clamped.text));
+        assert!(matches!(clamped.range, Some(Range::Lines(3, end)) if end < 100000));
+
+        let bytes = resources.read(&format!("{base}?bytes=4-8")).unwrap();
+        assert_eq!(bytes.text, &whole.text[4..8]);
+
+        // A URI returned by an earlier read can be requested again, even
+        // the empty range served for lines past the end.
+        assert_eq!(resources.read(&first.uri).unwrap().text, first.text);
+        let past_end = resources
+            .read(&format!("{base}?lines=100000-100001"))
+            .unwrap();
+        assert_eq!(past_end.text, "");
+        assert_eq!(resources.read(&past_end.uri).unwrap().range, past_end.range);
+    }
+
+    #[test]
+    fn rejects_malformed_ranges() {
+        for query in ["lines=0-3", "lines=5-2", "bytes=x-1", "chunk=1"] {
+            assert!(matches!(
+                Range::parse(query),
+                Err(ResourceError::InvalidRange(_))
+            ));
+        }
+        assert_eq!(Range::parse("").unwrap(), None);
+    }
+
+    #[test]
+    fn content_hash_is_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_eq!(content_hash("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn rejects_escapes_and_unknown_crates() {
+        let resources = resources();
+        assert!(matches!(
+            resources.read("minilib://source/minilib-core/../minilib-extra/src/lib.rs"),
+            Err(ResourceError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            resources.read("minilib://source/other/src/lib.rs"),
+            Err(ResourceError::UnknownCrate(_))
+        ));
+        assert!(matches!(
+            resources.read("file:///etc/passwd"),
+            Err(ResourceError::UnknownScheme(_))
+        ));
+        assert!(matches!(
+            resources.list(Some("abc")),
+            Err(ResourceError::InvalidCursor(_))
+        ));
+    }
+}
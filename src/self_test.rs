@@ -0,0 +1,224 @@
+//! Smoke tests run by `amari-mcp self-test`.
+//!
+//! The checks are derived from the index itself, so they work for any
+//! manifest: each one calls a registered tool with arguments taken from an
+//! indexed crate or item and asserts the response reflects that item.
+
+use crate::parser::index::ItemKind;
+use crate::tools::registry::ToolRegistry;
+use crate::tools::SharedState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Assertion applied to a tool's response.
+#[derive(Debug, Clone)]
+pub enum Expect {
+    /// The value at the JSON pointer equals the given value.
+    Equals(&'static str, Value),
+    /// The array at the JSON pointer holds an object whose `field` equals `value`.
+    Contains {
+        pointer: &'static str,
+        field: &'static str,
+        value: Value,
+    },
+    /// The array at the JSON pointer has exactly this many entries.
+    Len(&'static str, usize),
+}
+
+impl Expect {
+    /// Check a tool response. Responses carrying an `error` field always fail.
+    pub fn check(&self, response: &Value) -> Result<(), String> {
+        if let Some(error) = response.get("error") {
+            return Err(format!("tool reported error: {error}"));
+        }
+        match self {
+            Expect::Equals(pointer, expected) => match response.pointer(pointer) {
+                Some(actual) if actual == expected => Ok(()),
+                Some(actual) => Err(format!("{pointer} is {actual}, expected {expected}")),
+                None => Err(format!("{pointer} missing from response")),
+            },
+            Expect::Contains {
+                pointer,
+                field,
+                value,
+            } => {
+                let entries = array_at(response, pointer)?;
+                if entries.iter().any(|entry| entry.get(*field) == Some(value)) {
+                    Ok(())
+                } else {
+                    Err(format!("no entry in {pointer} has {field} = {value}"))
+                }
+            }
+            Expect::Len(pointer, expected) => {
+                let len = array_at(response, pointer)?.len();
+                if len == *expected {
+                    Ok(())
+                } else {
+                    Err(format!("{pointer} has {len} entries, expected {expected}"))
+                }
+            }
+        }
+    }
+}
+
+fn array_at<'a>(response: &'a Value, pointer: &str) -> Result<&'a Vec<Value>, String> {
+    response
+        .pointer(pointer)
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| format!("{pointer} is not an array"))
+}
+
+/// One tool invocation and the assertion on its response.
+#[derive(Debug, Clone)]
+pub struct SelfCheck {
+    pub description: String,
+    pub tool: &'static str,
+    pub arguments: Value,
+    pub expect: Expect,
+}
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Passed,
+    Failed(String),
+    /// The tool isn't registered under the configured profile.
+    Skipped,
+}
+
+/// Build the checks for an index.
+pub fn plan(state: &SharedState) -> Vec<SelfCheck> {
+    let index = &state.index;
+    let manifest = &state.manifest;
+    let mut checks = vec![
+        SelfCheck {
+            description: "server reports ok".to_string(),
+            tool: "server_info",
+            arguments: json!({}),
+            expect: Expect::Equals("/status", json!("ok")),
+        },
+        SelfCheck {
+            description: "feature map lists every default crate".to_string(),
+            tool: "feature_map",
+            arguments: json!({}),
+            expect: Expect::Len("/default_crates", manifest.crates.default.members.len()),
+        },
+        SelfCheck {
+            description: "dependency graph covers every crate".to_string(),
+            tool: "dependency_graph",
+            arguments: json!({}),
+            expect: Expect::Len("/graph", index.crates.len()),
+        },
+    ];
+
+    if let Some(crate_info) = index.crates.iter().min_by(|a, b| a.name.cmp(&b.name)) {
+        checks.push(SelfCheck {
+            description: format!("module overview of {}", crate_info.name),
+            tool: "module_overview",
+            arguments: json!({"crate": crate_info.name}),
+            expect: Expect::Equals("/crate", json!(crate_info.name)),
+        });
+        checks.push(SelfCheck {
+            description: format!("crate docs of {}", crate_info.name),
+            tool: "browse_docs",
+            arguments: json!({"crate": crate_info.name}),
+            expect: Expect::Equals("/crate", json!(crate_info.name)),
+        });
+    }
+
+    let sample_struct = index
+        .items_by_name
+        .values()
+        .flatten()
+        .filter(|item| matches!(item.kind, ItemKind::Struct { .. }))
+        .min_by(|a, b| a.full_path.cmp(&b.full_path));
+    if let Some(item) = sample_struct {
+        checks.push(SelfCheck {
+            description: format!("search finds {}", item.full_path),
+            tool: "api_search",
+            arguments: json!({
+                "query": item.name,
+                "kind": "struct",
+                "limit": manifest.server.limits.max_results,
+            }),
+            expect: Expect::Contains {
+                pointer: "/results",
+                field: "full_path",
+                value: json!(item.full_path),
+            },
+        });
+        checks.push(SelfCheck {
+            description: format!("type info for {}", item.full_path),
+            tool: "type_info",
+            arguments: json!({"name": item.full_path}),
+            expect: Expect::Equals("/full_path", json!(item.full_path)),
+        });
+    }
+
+    checks
+}
+
+/// Run every check in `plan` through the registry.
+pub async fn run(state: &Arc<SharedState>, tools: &ToolRegistry) -> Vec<(SelfCheck, Outcome)> {
+    let mut outcomes = Vec::new();
+    for check in plan(state) {
+        let outcome = if tools.get(check.tool).is_none() {
+            Outcome::Skipped
+        } else {
+            match tools.call_local(check.tool, check.arguments.clone()).await {
+                Ok(response) => match check.expect.check(&response) {
+                    Ok(()) => Outcome::Passed,
+                    Err(reason) => Outcome::Failed(reason),
+                },
+                Err(e) => Outcome::Failed(e.to_string()),
+            }
+        };
+        outcomes.push((check, outcome));
+    }
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equals_compares_value_at_pointer() {
+        let response = json!({"status": "ok", "nested": {"n": 3}});
+        assert!(Expect::Equals("/status", json!("ok"))
+            .check(&response)
+            .is_ok());
+        assert!(Expect::Equals("/nested/n", json!(3))
+            .check(&response)
+            .is_ok());
+        assert!(Expect::Equals("/status", json!("down"))
+            .check(&response)
+            .is_err());
+        assert!(Expect::Equals("/missing", json!(1))
+            .check(&response)
+            .is_err());
+    }
+
+    #[test]
+    fn contains_and_len_inspect_arrays() {
+        let response = json!({"results": [{"full_path": "a::B"}, {"full_path": "a::C"}]});
+        let contains = Expect::Contains {
+            pointer: "/results",
+            field: "full_path",
+            value: json!("a::C"),
+        };
+        assert!(contains.check(&response).is_ok());
+        assert!(Expect::Len("/results", 2).check(&response).is_ok());
+        assert!(Expect::Len("/results", 3).check(&response).is_err());
+        assert!(Expect::Len("/missing", 0).check(&response).is_err());
+    }
+
+    #[test]
+    fn error_responses_fail_every_expectation() {
+        let response = json!({"error": "Crate 'x' not found", "crate": "x"});
+        let err = Expect::Equals("/crate", json!("x"))
+            .check(&response)
+            .unwrap_err();
+        assert!(err.contains("not found"));
+    }
+}
@@ -0,0 +1,1277 @@
+//! Typed access to the indexed API, independent of MCP.
+//!
+//! [`AmariToolkit`] exposes the lookups behind the reference tools as plain
+//! Rust methods returning borrowed index data, so other programs can embed
+//! the index without a JSON-RPC transport. The MCP handlers in
+//! [`crate::tools`] are thin wrappers that parse arguments, call the toolkit,
+//! and render its results as JSON.
+
+use crate::config::LibraryManifest;
+use crate::diagnostics::{self, KnownError};
+use crate::doc_search::{self, Doc, DocHit, DocIndex, DocRef};
+use crate::embeddings;
+use crate::parser::changelog::{self, Release};
+use crate::parser::docs::extract_code_blocks;
+use crate::parser::index::{ApiIndex, ApiItem, CrateInfo, ItemKind, ModuleInfo, Validated};
+use crate::parser::sandbox::PathSandbox;
+use crate::parser::usages::{self, Usage};
+use crate::parser::workspace;
+use crate::tools::SharedState;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use thiserror::Error;
+use tokio::sync::OnceCell;
+
+/// Why a lookup found nothing. The messages are the `error` strings the MCP
+/// tools return.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LookupError {
+    #[error("Crate '{0}' not found")]
+    CrateNotFound(String),
+    #[error("Module '{module}' not found in crate '{crate_name}'")]
+    ModuleNotFound { crate_name: String, module: String },
+    #[error("Item '{item}' not found in crate '{crate_name}'")]
+    ItemNotFound { crate_name: String, item: String },
+    #[error("Type '{0}' not found")]
+    TypeNotFound(String),
+    #[error("No items found matching '{0}'")]
+    NoMatches(String),
+    #[error("Unknown baseline '{label}'. Configured baselines: {available}")]
+    UnknownBaseline { label: String, available: String },
+    #[error("Failed to index baseline '{label}': {reason}")]
+    BaselineFailed { label: String, reason: String },
+    #[error("Message matches no known error and names no indexed item")]
+    UnrecognizedError,
+    #[error("Unknown workspace '{name}'. Configured workspaces: {available}")]
+    UnknownWorkspace { name: String, available: String },
+    #[error("Failed to index workspace '{name}': {reason}")]
+    WorkspaceFailed { name: String, reason: String },
+    #[error("Embedding provider '{provider}' failed: {reason}")]
+    EmbeddingFailed { provider: String, reason: String },
+    #[error("No CHANGELOG.md or version tags found in {0}")]
+    NoReleaseNotes(String),
+    #[error("'{0}' is not a version")]
+    InvalidVersion(String),
+    #[error("Cannot read the workspace Cargo.toml: {0}")]
+    WorkspaceUnreadable(String),
+    #[error("Project '{path}' is not under a configured project root ({roots})")]
+    ProjectNotAllowed { path: String, roots: String },
+}
+
+/// Filters for [`AmariToolkit::search`].
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter<'a> {
+    /// Item kind label, as returned by [`kind_label`].
+    pub kind: Option<&'a str>,
+    /// Keep items whose path contains this crate name or alias.
+    pub crate_name: Option<&'a str>,
+    /// Maximum number of results.
+    pub limit: Option<usize>,
+}
+
+/// A type with the impl items attached to it.
+#[derive(Debug)]
+pub struct TypeDetails<'a> {
+    pub item: &'a ApiItem,
+    pub methods: Vec<&'a ApiItem>,
+}
+
+/// A resolved module and the crate containing it.
+#[derive(Debug)]
+pub struct ModuleView<'a> {
+    pub crate_info: &'a CrateInfo,
+    pub module: &'a ModuleInfo,
+}
+
+/// Crates and features of the library, from the manifest and index.
+#[derive(Debug)]
+pub struct FeatureMap<'a> {
+    pub library: &'a str,
+    pub default_crates: Vec<&'a str>,
+    pub optional: Vec<FeatureInfo<'a>>,
+}
+
+/// One optional Cargo feature and the crate it enables.
+#[derive(Debug)]
+pub struct FeatureInfo<'a> {
+    pub feature: &'a str,
+    pub crate_dir: &'a str,
+    pub alias: Option<&'a str>,
+    pub public_item_count: usize,
+}
+
+/// Code blocks taken from one item's doc comment.
+#[derive(Debug)]
+pub struct ItemExamples<'a> {
+    pub item: &'a ApiItem,
+    pub examples: Vec<String>,
+}
+
+/// What a project uses from the library that no longer fits the index.
+#[derive(Debug)]
+pub struct UpgradeReport<'a> {
+    pub files_scanned: usize,
+    pub usages_checked: usize,
+    pub findings: Vec<UpgradeFinding<'a>>,
+    /// Project files that couldn't be read or parsed.
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct UpgradeFinding<'a> {
+    pub usage: Usage,
+    pub issue: UpgradeIssue<'a>,
+}
+
+#[derive(Debug)]
+pub enum UpgradeIssue<'a> {
+    /// Every item the path could name is `#[deprecated]`.
+    Deprecated { item: &'a ApiItem },
+    /// No item or module has the path's final name; `suggestions` are
+    /// indexed items with similar names.
+    Missing { suggestions: Vec<&'a ApiItem> },
+}
+
+/// A compiler message matched against the error table and the index.
+#[derive(Debug)]
+pub struct ErrorExplanation<'a> {
+    pub code: Option<String>,
+    pub known: Option<&'static KnownError>,
+    /// Names quoted in the message that are, or resemble, indexed items.
+    pub names: Vec<NameReport<'a>>,
+}
+
+#[derive(Debug)]
+pub struct NameReport<'a> {
+    pub name: String,
+    /// Indexed items with exactly this name.
+    pub items: Vec<&'a ApiItem>,
+    /// Similarly named items, when `items` is empty.
+    pub suggestions: Vec<&'a ApiItem>,
+}
+
+/// How [`AmariToolkit::search_docs`] ranks documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchMode {
+    /// BM25 over exact words.
+    #[default]
+    Keyword,
+    /// Cosine similarity under the state's embedding provider.
+    Semantic,
+}
+
+/// Release notes for a version range, from [`AmariToolkit::changelog`].
+#[derive(Debug)]
+pub struct ReleaseNotes {
+    /// Changelog releases in the range, newest first as written.
+    pub releases: Vec<Release>,
+    /// Tags in the range, sorted, with the version each names.
+    pub tags: Vec<(String, String)>,
+    /// Whether the checkout has a `CHANGELOG.md`.
+    pub has_changelog: bool,
+}
+
+/// What a [`DocMatch`] documents.
+#[derive(Debug)]
+pub enum DocTarget<'a> {
+    Item(&'a ApiItem),
+    Module {
+        view: ModuleView<'a>,
+        /// Path from the crate's root module, e.g. `lib::shapes`.
+        path: &'a str,
+    },
+}
+
+/// A ranked result of [`AmariToolkit::search_docs`].
+#[derive(Debug)]
+pub struct DocMatch<'a> {
+    pub target: DocTarget<'a>,
+    pub score: f64,
+    /// The part of the docs around the first query term.
+    pub snippet: String,
+}
+
+/// Library-facing API over a validated index.
+pub struct AmariToolkit {
+    state: Arc<SharedState>,
+    /// Baseline indexes, built on first use.
+    baselines: BuildCache<Arc<ApiIndex<Validated>>>,
+    /// Full-text index over the docs, built on first use.
+    doc_index: OnceLock<DocIndex>,
+    /// Embeddings of `doc_index`'s documents, in order, once computed.
+    doc_vectors: Mutex<Option<Arc<Vec<Vec<f32>>>>>,
+    /// Toolkits over `[workspaces]` libraries, built on first use.
+    workspaces: BuildCache<Arc<AmariToolkit>>,
+    /// Toolkit over the state's latest re-index; see [`AmariToolkit::current`].
+    reindexed: Mutex<Option<Arc<AmariToolkit>>>,
+}
+
+impl AmariToolkit {
+    pub fn new(state: Arc<SharedState>) -> Self {
+        Self {
+            state,
+            baselines: Mutex::new(HashMap::new()),
+            doc_index: OnceLock::new(),
+            doc_vectors: Mutex::new(None),
+            workspaces: Mutex::new(HashMap::new()),
+            reindexed: Mutex::new(None),
+        }
+    }
+
+    /// Load the manifest at `manifest_path` and index the library it describes.
+    pub fn load(manifest_path: &Path) -> anyhow::Result<Self> {
+        let manifest = LibraryManifest::load(manifest_path)?;
+        let index = crate::parser::build_index(&manifest, manifest_path)?.validate()?;
+        Ok(Self::new(SharedState::new(index, manifest)))
+    }
+
+    pub fn state(&self) -> &Arc<SharedState> {
+        &self.state
+    }
+
+    pub fn index(&self) -> &ApiIndex<Validated> {
+        &self.state.index
+    }
+
+    pub fn manifest(&self) -> &LibraryManifest {
+        &self.state.manifest
+    }
+
+    /// Items whose name contains `query` (case-insensitive), filtered.
+    pub fn search(&self, query: &str, filter: &SearchFilter<'_>) -> Vec<&ApiItem> {
+        self.index()
+            .search(query)
+            .into_iter()
+            .filter(|item| {
+                filter
+                    .kind
+                    .is_none_or(|kind| kind_label(&item.kind) == kind)
+            })
+            .filter(|item| {
+                filter
+                    .crate_name
+                    .is_none_or(|crate_name| item.full_path.contains(crate_name))
+            })
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// The struct, enum, trait or type alias named `name` (or whose path ends
+    /// with it), with the impl items for that type.
+    pub fn type_info(&self, name: &str) -> Result<TypeDetails<'_>, LookupError> {
+        let item = self
+            .index()
+            .search(name)
+            .into_iter()
+            .filter(|item| item.name == name || item.full_path.ends_with(name))
+            .find(|item| {
+                matches!(
+                    item.kind,
+                    ItemKind::Struct { .. }
+                        | ItemKind::Enum { .. }
+                        | ItemKind::Trait { .. }
+                        | ItemKind::TypeAlias
+                )
+            })
+            .ok_or_else(|| LookupError::TypeNotFound(name.to_string()))?;
+
+        let methods = self
+            .index()
+            .search(&item.name)
+            .into_iter()
+            .filter(|candidate| match &candidate.kind {
+                ItemKind::Impl { self_type, .. } => self_type.contains(&item.name),
+                _ => false,
+            })
+            .collect();
+
+        Ok(TypeDetails { item, methods })
+    }
+
+    /// A crate by name or alias.
+    pub fn crate_info(&self, name: &str) -> Result<&CrateInfo, LookupError> {
+        self.index()
+            .get_crate(name)
+            .ok_or_else(|| LookupError::CrateNotFound(name.to_string()))
+    }
+
+    /// Indexed crates as `name (alias)` labels, for not-found messages.
+    pub fn crate_labels(&self) -> Vec<String> {
+        self.index()
+            .crates
+            .iter()
+            .map(|c| match &c.alias {
+                Some(alias) => format!("{} ({alias})", c.name),
+                None => c.name.clone(),
+            })
+            .collect()
+    }
+
+    /// A module by `::`-separated path within a crate, or the crate's root
+    /// module when `path` is `None`.
+    pub fn module(
+        &self,
+        crate_name: &str,
+        path: Option<&str>,
+    ) -> Result<ModuleView<'_>, LookupError> {
+        let crate_info = self.crate_info(crate_name)?;
+        let module = match path {
+            Some(path) => find_module(&crate_info.modules, path),
+            None => crate_info.modules.first(),
+        };
+        let module = module.ok_or_else(|| LookupError::ModuleNotFound {
+            crate_name: crate_name.to_string(),
+            module: path.unwrap_or("<root>").to_string(),
+        })?;
+        Ok(ModuleView { crate_info, module })
+    }
+
+    /// An item named exactly `item` within a crate.
+    pub fn item_docs(&self, crate_name: &str, item: &str) -> Result<&ApiItem, LookupError> {
+        self.crate_info(crate_name)?;
+        self.index()
+            .search(item)
+            .into_iter()
+            .find(|i| i.name == item && i.full_path.contains(crate_name))
+            .ok_or_else(|| LookupError::ItemNotFound {
+                crate_name: crate_name.to_string(),
+                item: item.to_string(),
+            })
+    }
+
+    /// Default crates and optional features, optionally limited to one feature.
+    pub fn features(&self, feature: Option<&str>) -> FeatureMap<'_> {
+        let manifest = self.manifest();
+        let default_crates = self
+            .index()
+            .crates
+            .iter()
+            .filter(|c| c.feature_gate.is_none())
+            .map(|c| c.name.as_str())
+            .collect();
+
+        let optional = manifest
+            .crates
+            .optional
+            .iter()
+            .filter(|(name, _)| feature.is_none_or(|f| f == name.as_str()))
+            .map(|(name, dir_name)| FeatureInfo {
+                feature: name,
+                crate_dir: dir_name,
+                alias: manifest.alias_for(dir_name),
+                public_item_count: self.index().feature_items(name).len(),
+            })
+            .collect();
+
+        FeatureMap {
+            library: &manifest.library.name,
+            default_crates,
+            optional,
+        }
+    }
+
+    /// Map capability names onto the umbrella crate's declared `[features]`,
+    /// read from the workspace root Cargo.toml at call time.
+    pub fn resolve_features(
+        &self,
+        capabilities: &[String],
+    ) -> Result<workspace::FeatureResolution, LookupError> {
+        let manifest = self.manifest();
+        let cargo_toml = manifest
+            .resolve_source_path(&manifest.manifest_path)
+            .join(&manifest.workspace.root_cargo_toml);
+        let metadata = workspace::read_workspace_metadata(&cargo_toml)
+            .map_err(|e| LookupError::WorkspaceUnreadable(format!("{e:#}")))?;
+        Ok(workspace::resolve_capabilities(
+            &metadata,
+            &manifest.library.name,
+            &manifest.library.version,
+            &manifest.crates.optional,
+            &manifest.aliases,
+            capabilities,
+        ))
+    }
+
+    /// `CHANGELOG.md` releases and git tags of the library checkout for
+    /// versions in `(from, to]`; either bound may be open.
+    pub fn changelog(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<ReleaseNotes, LookupError> {
+        for bound in [from, to].into_iter().flatten() {
+            if changelog::compare_versions(bound, bound).is_none() {
+                return Err(LookupError::InvalidVersion(bound.to_string()));
+            }
+        }
+
+        let manifest = self.manifest();
+        let root = manifest.resolve_source_path(&manifest.manifest_path);
+        let text = std::fs::read_to_string(root.join("CHANGELOG.md")).ok();
+        let tags: Vec<(String, String)> = changelog::read_tags(&root)
+            .into_iter()
+            .filter_map(|tag| {
+                let version = changelog::tag_version(&tag)?.to_string();
+                Some((tag, version))
+            })
+            .collect();
+        if text.is_none() && tags.is_empty() {
+            return Err(LookupError::NoReleaseNotes(root.display().to_string()));
+        }
+
+        let releases = text
+            .as_deref()
+            .map(changelog::parse_changelog)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|release| changelog::in_range(&release.version, from, to))
+            .collect();
+        Ok(ReleaseNotes {
+            releases,
+            tags: tags
+                .into_iter()
+                .filter(|(_, version)| changelog::in_range(version, from, to))
+                .collect(),
+            has_changelog: text.is_some(),
+        })
+    }
+
+    /// Workspace-internal dependencies of each indexed crate.
+    pub fn dependency_graph(&self) -> HashMap<String, Vec<String>> {
+        let crate_dirs: Vec<(String, &Path)> = self
+            .index()
+            .crates
+            .iter()
+            .map(|c| (c.name.clone(), c.source_dir.as_path()))
+            .collect();
+        workspace::build_dependency_graph(&crate_dirs)
+    }
+
+    /// Doc-comment code blocks of every item named `name` (or whose path
+    /// ends with it). Items without examples are left out.
+    pub fn usage_examples(&self, name: &str) -> Result<Vec<ItemExamples<'_>>, LookupError> {
+        let matching: Vec<&ApiItem> = self
+            .index()
+            .search(name)
+            .into_iter()
+            .filter(|i| i.name == name || i.full_path.ends_with(name))
+            .collect();
+
+        if matching.is_empty() {
+            return Err(LookupError::NoMatches(name.to_string()));
+        }
+
+        Ok(matching
+            .into_iter()
+            .map(|item| ItemExamples {
+                item,
+                examples: extract_code_blocks(&item.doc_comment),
+            })
+            .filter(|e| !e.examples.is_empty())
+            .collect())
+    }
+
+    /// Scan the Rust files under `project` for paths into the library's
+    /// crates and report those naming deprecated or missing items.
+    ///
+    /// `project` must lie under one of `[server] project_roots`. Paths are
+    /// matched by their final segment, narrowed to the preceding type's
+    /// methods when there is one; method-call syntax (`p.norm()`) is not
+    /// resolved, since that needs type inference.
+    pub fn upgrade_advice(&self, project: &Path) -> Result<UpgradeReport<'_>, LookupError> {
+        let roots = self.manifest().resolve_project_roots();
+        let not_allowed = || LookupError::ProjectNotAllowed {
+            path: project.display().to_string(),
+            roots: if roots.is_empty() {
+                "none configured".to_string()
+            } else {
+                roots
+                    .iter()
+                    .map(|r| r.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        };
+        let (first, rest) = roots.split_first().ok_or_else(not_allowed)?;
+        let project = PathSandbox::new(first, rest)
+            .check(project)
+            .map_err(|_| not_allowed())?;
+
+        let index = self.index();
+        let library = self.manifest().library.name.replace('-', "_");
+        let mut crate_idents = vec![library];
+        crate_idents.extend(index.crates.iter().map(|c| c.name.replace('-', "_")));
+
+        let mut known_modules: HashSet<&str> = HashSet::new();
+        for info in &index.crates {
+            known_modules.extend(info.alias.as_deref());
+            collect_module_names(&info.modules, &mut known_modules);
+        }
+
+        let scan = usages::scan_project(&project, &crate_idents);
+        let usages_checked = scan.usages.len();
+        let findings = scan
+            .usages
+            .into_iter()
+            .filter_map(|usage| {
+                let issue = self.check_usage(&usage, &crate_idents, &known_modules)?;
+                Some(UpgradeFinding { usage, issue })
+            })
+            .collect();
+
+        Ok(UpgradeReport {
+            files_scanned: scan.files_scanned,
+            usages_checked,
+            findings,
+            errors: scan.errors,
+        })
+    }
+
+    fn check_usage(
+        &self,
+        usage: &Usage,
+        crate_idents: &[String],
+        known_modules: &HashSet<&str>,
+    ) -> Option<UpgradeIssue<'_>> {
+        let name = usage.path.last()?;
+        if crate_idents.contains(name) || known_modules.contains(name.as_str()) {
+            return None;
+        }
+
+        let index = self.index();
+        let Some(candidates) = index.items_by_name.get(name.as_str()) else {
+            return Some(UpgradeIssue::Missing {
+                suggestions: similar_items(index, name, 3),
+            });
+        };
+
+        let parent = usage.path.iter().rev().nth(1).map(String::as_str);
+        let methods: Vec<&ApiItem> = candidates
+            .iter()
+            .filter(|item| {
+                matches!(&item.kind, ItemKind::Impl { self_type, .. } if Some(self_type.as_str()) == parent)
+            })
+            .collect();
+        let mut candidates: Vec<&ApiItem> = if methods.is_empty() {
+            candidates.iter().collect()
+        } else {
+            methods
+        };
+        if candidates.iter().all(|item| item.deprecated.is_some()) {
+            return candidates
+                .pop()
+                .map(|item| UpgradeIssue::Deprecated { item });
+        }
+        None
+    }
+
+    /// Explain a rustc error message: its entry in
+    /// [`diagnostics::KNOWN_ERRORS`], plus what the index knows about each
+    /// name the message quotes. Crate and module names are skipped.
+    pub fn explain_error(&self, message: &str) -> Result<ErrorExplanation<'_>, LookupError> {
+        let parsed = diagnostics::parse_message(message);
+        let known = diagnostics::lookup(parsed.code.as_deref(), message);
+
+        let index = self.index();
+        let mut skip: HashSet<String> = index
+            .crates
+            .iter()
+            .map(|c| c.name.replace('-', "_"))
+            .collect();
+        let mut modules = HashSet::new();
+        for info in &index.crates {
+            collect_module_names(&info.modules, &mut modules);
+        }
+        skip.extend(modules.into_iter().map(String::from));
+
+        let names: Vec<NameReport<'_>> = parsed
+            .names
+            .into_iter()
+            .filter(|name| !skip.contains(name))
+            .filter_map(|name| {
+                let items: Vec<&ApiItem> = index
+                    .items_by_name
+                    .get(&name)
+                    .map(|items| items.iter().take(10).collect())
+                    .unwrap_or_default();
+                let suggestions = if items.is_empty() {
+                    similar_items(index, &name, 3)
+                } else {
+                    Vec::new()
+                };
+                (!items.is_empty() || !suggestions.is_empty()).then_some(NameReport {
+                    name,
+                    items,
+                    suggestions,
+                })
+            })
+            .collect();
+
+        if known.is_none() && names.is_empty() {
+            return Err(LookupError::UnrecognizedError);
+        }
+        Ok(ErrorExplanation {
+            code: parsed.code,
+            known,
+            names,
+        })
+    }
+
+    /// Item and module docs ranked against `query`, best first, optionally
+    /// within one crate (name or alias).
+    pub fn search_docs(
+        &self,
+        query: &str,
+        mode: SearchMode,
+        crate_name: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<DocMatch<'_>>, LookupError> {
+        let within = crate_name.map(|name| self.crate_info(name)).transpose()?;
+        let docs = self.doc_index.get_or_init(|| DocIndex::build(self.index()));
+        let keep = |doc: &Doc| within.is_none_or(|info| doc.crate_name == info.name);
+        let hits = match mode {
+            SearchMode::Keyword => docs.search(query, keep),
+            SearchMode::Semantic => self.semantic_hits(docs, query, keep)?,
+        };
+
+        let matches = hits
+            .into_iter()
+            .filter_map(|hit| {
+                let doc = docs.doc(hit.doc);
+                let target = match &doc.target {
+                    DocRef::Item { name, ordinal } => {
+                        DocTarget::Item(self.index().items_by_name.get(name)?.get(*ordinal)?)
+                    }
+                    DocRef::Module { crate_name, path } => DocTarget::Module {
+                        view: self.module(crate_name, Some(path)).ok()?,
+                        path,
+                    },
+                };
+                Some(DocMatch {
+                    target,
+                    score: hit.score,
+                    snippet: doc_search::snippet(&doc.text, query),
+                })
+            })
+            .take(limit)
+            .collect();
+        Ok(matches)
+    }
+
+    /// Documents by cosine similarity to `query` under the configured
+    /// embedding provider, dropping those with nothing in common.
+    fn semantic_hits(
+        &self,
+        docs: &DocIndex,
+        query: &str,
+        keep: impl Fn(&Doc) -> bool,
+    ) -> Result<Vec<DocHit>, LookupError> {
+        let vectors = self.doc_vectors(docs)?;
+        let provider = &self.state.embeddings;
+        let failed = |reason: String| LookupError::EmbeddingFailed {
+            provider: provider.name().to_string(),
+            reason,
+        };
+        let query = provider
+            .embed(&[query])
+            .map_err(|e| failed(format!("{e:#}")))?
+            .pop()
+            .ok_or_else(|| failed("no vector returned for the query".to_string()))?;
+
+        let mut hits: Vec<DocHit> = vectors
+            .iter()
+            .enumerate()
+            .filter(|(id, _)| keep(docs.doc(*id)))
+            .map(|(doc, vector)| DocHit {
+                doc,
+                score: f64::from(embeddings::cosine(&query, vector)),
+            })
+            .filter(|hit| hit.score > 0.0)
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.doc.cmp(&b.doc)));
+        Ok(hits)
+    }
+
+    /// Embeddings of every document, computed on first use and cached. A
+    /// failed attempt isn't cached, so a remote provider can recover.
+    fn doc_vectors(&self, docs: &DocIndex) -> Result<Arc<Vec<Vec<f32>>>, LookupError> {
+        let mut cache = self.doc_vectors.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(vectors) = cache.as_ref() {
+            return Ok(vectors.clone());
+        }
+
+        let provider = &self.state.embeddings;
+        let failed = |reason: String| LookupError::EmbeddingFailed {
+            provider: provider.name().to_string(),
+            reason,
+        };
+        let texts: Vec<String> = docs
+            .docs()
+            .iter()
+            .map(|doc| format!("{} {}", doc.title, doc.text))
+            .collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let vectors = provider
+            .embed(&texts)
+            .map_err(|e| failed(format!("{e:#}")))?;
+        if vectors.len() != texts.len() {
+            return Err(failed(format!(
+                "{} vectors returned for {} documents",
+                vectors.len(),
+                texts.len()
+            )));
+        }
+
+        let vectors = Arc::new(vectors);
+        *cache = Some(vectors.clone());
+        Ok(vectors)
+    }
+
+    /// The crate an item was indexed from, by the first segment of its path.
+    pub fn crate_of(&self, item: &ApiItem) -> Option<&CrateInfo> {
+        let crate_name = item.full_path.split("::").next()?;
+        self.index().get_crate(crate_name)
+    }
+
+    /// This toolkit, or once the watch has re-indexed the source (see
+    /// [`SharedState::reindex`]), a toolkit over the new index. The
+    /// replacement starts with empty doc-search, workspace and baseline
+    /// caches.
+    pub fn current(self: &Arc<Self>) -> Arc<Self> {
+        let state = self.state.current();
+        if Arc::ptr_eq(&state, &self.state) {
+            return self.clone();
+        }
+        let mut reindexed = self.reindexed.lock().unwrap_or_else(|e| e.into_inner());
+        match &*reindexed {
+            Some(toolkit) if Arc::ptr_eq(&toolkit.state, &state) => toolkit.clone(),
+            _ => reindexed.insert(Arc::new(Self::new(state))).clone(),
+        }
+    }
+
+    /// The [`current`](Self::current) toolkit when `name` is `None`, else the
+    /// toolkit over the named `[workspaces]` library, indexed from its own
+    /// manifest on first use and cached. Workspace toolkits share this one's
+    /// embedding provider.
+    pub async fn workspace(self: &Arc<Self>, name: Option<&str>) -> Result<Arc<Self>, LookupError> {
+        let current = self.current();
+        let Some(name) = name else {
+            return Ok(current);
+        };
+        current.named_workspace(name).await
+    }
+
+    async fn named_workspace(&self, name: &str) -> Result<Arc<Self>, LookupError> {
+        let manifest = self.manifest();
+        let path =
+            manifest
+                .resolve_workspace(name)
+                .ok_or_else(|| LookupError::UnknownWorkspace {
+                    name: name.to_string(),
+                    available: {
+                        let mut available: Vec<&str> =
+                            manifest.workspaces.keys().map(String::as_str).collect();
+                        available.sort_unstable();
+                        available.join(", ")
+                    },
+                })?;
+
+        let cell = cache_cell(&self.workspaces, name);
+        let toolkit = cell
+            .get_or_try_init(|| async move {
+                let embeddings = self.state.embeddings.clone();
+                let state = blocking(move || {
+                    let workspace = LibraryManifest::load(&path)?;
+                    let index = crate::parser::build_index(&workspace, &path)?.validate()?;
+                    Ok(SharedState::with_embeddings(index, workspace, embeddings))
+                })
+                .await
+                .map_err(|e| LookupError::WorkspaceFailed {
+                    name: name.to_string(),
+                    reason: format!("{e:#}"),
+                })?;
+                Ok::<_, LookupError>(Arc::new(Self::new(state)))
+            })
+            .await?;
+        Ok(toolkit.clone())
+    }
+
+    /// The index of a `[baselines]` checkout, built on first use and cached.
+    pub async fn baseline(&self, label: &str) -> Result<Arc<ApiIndex<Validated>>, LookupError> {
+        let manifest = self.manifest();
+        let source_path = manifest
+            .baselines
+            .get(label)
+            .ok_or_else(|| LookupError::UnknownBaseline {
+                label: label.to_string(),
+                available: {
+                    let mut available: Vec<&str> =
+                        manifest.baselines.keys().map(String::as_str).collect();
+                    available.sort_unstable();
+                    available.join(", ")
+                },
+            })?
+            .clone();
+
+        let cell = cache_cell(&self.baselines, label);
+        let index = cell
+            .get_or_try_init(|| async move {
+                let state = self.state.clone();
+                let index = blocking(move || {
+                    let manifest = &state.manifest;
+                    let index = crate::parser::build_index_with_source(
+                        manifest,
+                        &manifest.manifest_path,
+                        &source_path,
+                    )?;
+                    Ok(index.validate()?)
+                })
+                .await
+                .map_err(|e| LookupError::BaselineFailed {
+                    label: label.to_string(),
+                    reason: format!("{e:#}"),
+                })?;
+                Ok::<_, LookupError>(Arc::new(index))
+            })
+            .await?;
+        Ok(index.clone())
+    }
+}
+
+/// Lazily built values keyed by name. The map lock is only held to find a
+/// name's cell, so building one value doesn't block lookups of others, and
+/// concurrent first calls for the same name wait for a single build.
+type BuildCache<T> = Mutex<HashMap<String, Arc<OnceCell<T>>>>;
+
+fn cache_cell<T>(cache: &BuildCache<T>, name: &str) -> Arc<OnceCell<T>> {
+    cache
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(name.to_string())
+        .or_default()
+        .clone()
+}
+
+/// Run an index build on the blocking pool, off the async workers.
+async fn blocking<T: Send + 'static>(
+    build: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> anyhow::Result<T> {
+    tokio::task::spawn_blocking(build).await?
+}
+
+/// Lowercase label for an item kind, as used by the `kind` search filter.
+pub fn kind_label(kind: &ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Function { .. } => "function",
+        ItemKind::Struct { .. } => "struct",
+        ItemKind::Enum { .. } => "enum",
+        ItemKind::Trait { .. } => "trait",
+        ItemKind::TypeAlias => "type",
+        ItemKind::Const { .. } => "const",
+        ItemKind::Impl { .. } => "impl",
+        ItemKind::ReExport { .. } => "reexport",
+    }
+}
+
+fn collect_module_names<'a>(modules: &'a [ModuleInfo], out: &mut HashSet<&'a str>) {
+    for module in modules {
+        out.insert(&module.name);
+        collect_module_names(&module.submodules, out);
+    }
+}
+
+/// Up to `limit` items whose names are within a small edit distance of
+/// `name`, closest first.
+fn similar_items<'a>(index: &'a ApiIndex<Validated>, name: &str, limit: usize) -> Vec<&'a ApiItem> {
+    let target = name.to_lowercase();
+    let max_distance = (target.chars().count() / 3).max(1);
+    let mut close: Vec<(usize, &str)> = index
+        .items_by_name
+        .keys()
+        .map(|candidate| {
+            (
+                edit_distance(&target, &candidate.to_lowercase()),
+                candidate.as_str(),
+            )
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    close.sort_unstable();
+    close
+        .into_iter()
+        .flat_map(|(_, candidate)| index.items_by_name[candidate].iter())
+        .take(limit)
+        .collect()
+}
+
+/// Levenshtein distance between two strings, by characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn find_module<'a>(modules: &'a [ModuleInfo], path: &str) -> Option<&'a ModuleInfo> {
+    let mut modules = modules;
+    let mut found = None;
+    for part in path.split("::") {
+        let module = modules.iter().find(|m| m.name == part)?;
+        modules = &module.submodules;
+        found = Some(module);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::{BagOfWords, EmbeddingProvider};
+
+    fn toolkit() -> AmariToolkit {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/minilib.toml");
+        AmariToolkit::load(&manifest).expect("fixture should index")
+    }
+
+    #[test]
+    fn search_applies_kind_and_limit() {
+        let toolkit = toolkit();
+        assert!(toolkit.search("", &SearchFilter::default()).len() > 1);
+
+        let enums = toolkit.search(
+            "",
+            &SearchFilter {
+                kind: Some("enum"),
+                ..Default::default()
+            },
+        );
+        assert!(!enums.is_empty());
+        assert!(enums.iter().all(|item| kind_label(&item.kind) == "enum"));
+
+        let limited = toolkit.search(
+            "",
+            &SearchFilter {
+                limit: Some(1),
+                ..Default::default()
+            },
+        );
+        assert_eq!(limited.len(), 1);
+    }
+
+    #[test]
+    fn kind_labels_match_filter_values() {
+        let function = ItemKind::Function {
+            is_async: false,
+            is_unsafe: false,
+        };
+        assert_eq!(kind_label(&function), "function");
+        assert_eq!(kind_label(&ItemKind::TypeAlias), "type");
+        assert_eq!(
+            kind_label(&ItemKind::Struct {
+                fields: crate::parser::index::FieldKind::Unit
+            }),
+            "struct"
+        );
+    }
+
+    #[test]
+    fn type_info_finds_types_only() {
+        let toolkit = toolkit();
+        assert_eq!(toolkit.type_info("Point").unwrap().item.name, "Point");
+        assert_eq!(
+            toolkit.type_info("distance").unwrap_err().to_string(),
+            "Type 'distance' not found"
+        );
+    }
+
+    #[test]
+    fn module_resolves_paths_and_aliases() {
+        let toolkit = toolkit();
+        let view = toolkit.module("core", Some("lib::shapes")).unwrap();
+        assert_eq!(view.crate_info.name, "minilib-core");
+        assert_eq!(view.module.name, "shapes");
+        assert_eq!(toolkit.module("core", None).unwrap().module.name, "lib");
+        assert_eq!(
+            toolkit.module("core", Some("lib::nope")).unwrap_err(),
+            LookupError::ModuleNotFound {
+                crate_name: "core".to_string(),
+                module: "lib::nope".to_string(),
+            }
+        );
+        assert!(matches!(
+            toolkit.module("missing", None),
+            Err(LookupError::CrateNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn usage_examples_skips_items_without_code() {
+        let toolkit = toolkit();
+        let examples = toolkit.usage_examples("Point").unwrap();
+        assert!(examples.iter().all(|e| !e.examples.is_empty()));
+        assert!(examples.iter().any(|e| e.item.name == "Point"));
+        assert!(matches!(
+            toolkit.usage_examples("Nowhere"),
+            Err(LookupError::NoMatches(_))
+        ));
+    }
+
+    #[test]
+    fn features_filter_by_name() {
+        let toolkit = toolkit();
+        let map = toolkit.features(None);
+        assert_eq!(map.library, "minilib");
+        assert_eq!(map.default_crates, vec!["minilib-core"]);
+        assert_eq!(map.optional.len(), 1);
+        assert!(toolkit.features(Some("gpu")).optional.is_empty());
+    }
+
+    #[test]
+    fn upgrade_advice_flags_deprecated_and_missing_items() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(
+            project.path().join("main.rs"),
+            "use minilib_core::{shapes, Point, Pointt};\n\
+             fn main() {\n\
+                 let p = minilib_core::Point::new(1.0, 2.0);\n\
+                 let _ = minilib_core::Point::magnitude(&p);\n\
+                 let _ = minilib_core::origin();\n\
+             }\n",
+        )
+        .unwrap();
+
+        let manifest_path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/minilib.toml");
+        let mut manifest = LibraryManifest::load(&manifest_path).unwrap();
+        let index = crate::parser::build_index(&manifest, &manifest_path)
+            .unwrap()
+            .validate()
+            .unwrap();
+        manifest.server.project_roots = vec![project.path().display().to_string()];
+        let toolkit = AmariToolkit::new(SharedState::new(index, manifest));
+
+        let report = toolkit.upgrade_advice(project.path()).unwrap();
+        assert_eq!(report.files_scanned, 1);
+        let issues: Vec<(String, &UpgradeIssue)> = report
+            .findings
+            .iter()
+            .map(|f| (f.usage.display_path(), &f.issue))
+            .collect();
+        assert_eq!(issues.len(), 3, "{issues:?}");
+        assert!(matches!(
+            issues[0],
+            (ref path, UpgradeIssue::Missing { suggestions })
+                if path == "minilib_core::Pointt" && suggestions[0].name == "Point"
+        ));
+        assert!(matches!(
+            issues[1],
+            (ref path, UpgradeIssue::Deprecated { item })
+                if path == "minilib_core::Point::magnitude"
+                    && item.deprecated.as_deref().unwrap().starts_with("since 0.2.0")
+        ));
+        assert!(matches!(
+            issues[2],
+            (ref path, UpgradeIssue::Missing { .. }) if path == "minilib_core::origin"
+        ));
+    }
+
+    #[test]
+    fn resolve_features_reads_workspace_metadata() {
+        let resolution = toolkit().resolve_features(&["extra".to_string()]).unwrap();
+        assert_eq!(resolution.package, "minilib");
+        assert_eq!(resolution.version, "0.2.0");
+        assert_eq!(resolution.features, vec!["extra"]);
+        assert!(resolution.unresolved.is_empty());
+    }
+
+    #[test]
+    fn explain_error_reports_gates_and_suggestions() {
+        let toolkit = toolkit();
+        let explanation = toolkit
+            .explain_error("error[E0425]: cannot find function `midpoint` in crate `minilib_extra`")
+            .unwrap();
+        assert_eq!(explanation.known.unwrap().code, "E0425");
+        assert_eq!(explanation.names.len(), 1);
+        let midpoint = explanation.names[0].items[0];
+        assert_eq!(
+            toolkit.crate_of(midpoint).unwrap().feature_gate.as_deref(),
+            Some("extra")
+        );
+
+        let explanation = toolkit
+            .explain_error("error[E0412]: cannot find type `Pointe` in this scope")
+            .unwrap();
+        assert_eq!(explanation.names[0].suggestions[0].name, "Point");
+
+        assert_eq!(
+            toolkit.explain_error("the build was slow").unwrap_err(),
+            LookupError::UnrecognizedError
+        );
+    }
+
+    #[test]
+    fn search_docs_ranks_items_and_modules() {
+        let toolkit = toolkit();
+        let matches = toolkit
+            .search_docs("euclidean distance", SearchMode::Keyword, None, 10)
+            .unwrap();
+        let DocTarget::Item(first) = &matches[0].target else {
+            panic!("expected an item first");
+        };
+        assert_eq!(first.name, "distance");
+        assert!(matches[0].snippet.contains("Euclidean"));
+
+        let matches = toolkit
+            .search_docs("shapes", SearchMode::Keyword, None, 10)
+            .unwrap();
+        assert!(matches.iter().any(
+            |m| matches!(&m.target, DocTarget::Module { path, .. } if *path == "lib::shapes")
+        ));
+
+        let matches = toolkit
+            .search_docs("points", SearchMode::Keyword, Some("extra"), 10)
+            .unwrap();
+        assert!(!matches.is_empty());
+        assert!(matches.iter().all(|m| match &m.target {
+            DocTarget::Item(item) => item.full_path.starts_with("minilib-extra"),
+            DocTarget::Module { view, .. } => view.crate_info.name == "minilib-extra",
+        }));
+        assert!(toolkit
+            .search_docs("points", SearchMode::Keyword, Some("nope"), 10)
+            .is_err());
+    }
+
+    #[test]
+    fn semantic_search_matches_word_forms() {
+        let toolkit = toolkit();
+        assert!(toolkit
+            .search_docs("distances between points", SearchMode::Keyword, None, 10)
+            .unwrap()
+            .iter()
+            .all(|m| !matches!(m.target, DocTarget::Item(item) if item.name == "distance")));
+
+        let matches = toolkit
+            .search_docs("distances between points", SearchMode::Semantic, None, 10)
+            .unwrap();
+        let DocTarget::Item(first) = &matches[0].target else {
+            panic!("expected an item first");
+        };
+        assert_eq!(first.name, "distance");
+        assert!(matches
+            .iter()
+            .all(|m| m.score > 0.0 && m.score <= 1.0 + 1e-6));
+    }
+
+    /// Embeds documents with [`BagOfWords`] but rejects every query.
+    struct RejectsQueries;
+
+    impl EmbeddingProvider for RejectsQueries {
+        fn name(&self) -> &str {
+            "rejects-queries"
+        }
+
+        fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            if texts.len() == 1 {
+                return Err(anyhow::anyhow!("quota exceeded").context("embedding request failed"));
+            }
+            BagOfWords.embed(texts)
+        }
+    }
+
+    #[test]
+    fn semantic_search_reports_the_provider_error() {
+        let manifest_path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/minilib.toml");
+        let manifest = LibraryManifest::load(&manifest_path).unwrap();
+        let index = crate::parser::build_index(&manifest, &manifest_path)
+            .unwrap()
+            .validate()
+            .unwrap();
+        let toolkit = AmariToolkit::new(SharedState::with_embeddings(
+            index,
+            manifest,
+            Arc::new(RejectsQueries),
+        ));
+        let err = toolkit
+            .search_docs("points", SearchMode::Semantic, None, 10)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Embedding provider 'rejects-queries' failed: embedding request failed: quota exceeded"
+        );
+    }
+
+    #[test]
+    fn upgrade_advice_requires_a_project_root() {
+        let err = toolkit().upgrade_advice(Path::new("/")).unwrap_err();
+        assert!(matches!(err, LookupError::ProjectNotAllowed { .. }));
+        assert!(err.to_string().contains("minilib-app"));
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("rotor", "rotor"), 0);
+        assert_eq!(edit_distance("rotor", "rotors"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[tokio::test]
+    async fn workspaces_index_their_own_manifest() {
+        let toolkit = Arc::new(toolkit());
+        assert!(Arc::ptr_eq(
+            &toolkit.workspace(None).await.unwrap(),
+            &toolkit
+        ));
+
+        let fork = toolkit.workspace(Some("fork")).await.unwrap();
+        assert_eq!(fork.manifest().library.name, "minilib-fork");
+        assert_eq!(fork.type_info("Point").unwrap().item.name, "Point");
+        assert!(fork.crate_info("extra").is_err());
+        assert!(Arc::ptr_eq(
+            &toolkit.workspace(Some("fork")).await.unwrap(),
+            &fork
+        ));
+
+        let err = toolkit.workspace(Some("nope")).await.err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Unknown workspace 'nope'. Configured workspaces: fork"
+        );
+    }
+
+    #[test]
+    fn changelog_filters_releases_by_range() {
+        let toolkit = toolkit();
+        let notes = toolkit.changelog(Some("0.1"), Some("0.2")).unwrap();
+        assert!(notes.has_changelog);
+        let versions: Vec<&str> = notes.releases.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, vec!["0.2.0"]);
+        assert_eq!(notes.releases[0].sections[0].heading, "Deprecated");
+
+        let all = toolkit.changelog(None, None).unwrap();
+        assert_eq!(all.releases.len(), 3);
+        assert_eq!(
+            toolkit.changelog(Some("latest"), None).unwrap_err(),
+            LookupError::InvalidVersion("latest".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_baseline_lists_configured_labels() {
+        let err = toolkit().baseline("9.9").await.err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Unknown baseline '9.9'. Configured baselines: 0.1"
+        );
+    }
+}
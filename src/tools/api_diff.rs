@@ -0,0 +1,137 @@
+use crate::parser::diff::{self, ChangedItem};
+use crate::parser::index::ApiItem;
+use crate::toolkit::{kind_label, AmariToolkit};
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub struct ApiDiffHandler {
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiDiffRequest {
+    pub baseline: String,
+    #[serde(rename = "crate")]
+    pub crate_name: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiDiffResponse<'a> {
+    pub baseline: &'a str,
+    pub current_version: &'a str,
+    pub summary: DiffSummary,
+    pub added: Vec<DiffItem<'a>>,
+    pub removed: Vec<DiffItem<'a>>,
+    pub changed: Vec<ChangedSignature<'a>>,
+}
+
+/// Entry counts before `limit` truncation.
+#[derive(Debug, Serialize)]
+pub struct DiffSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffItem<'a> {
+    pub full_path: &'a str,
+    pub kind: &'static str,
+    pub signature: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChangedSignature<'a> {
+    pub full_path: &'a str,
+    pub kind: &'static str,
+    pub old_signature: &'a str,
+    pub new_signature: &'a str,
+}
+
+#[async_trait]
+impl ToolHandler for ApiDiffHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::library_tool_info(
+            &self.toolkit,
+            "api_diff",
+            "Compare the public API against an older checkout of the library and list added, removed, and changed items",
+            json!({
+                "type": "object",
+                "properties": {
+                    "baseline": {
+                        "type": "string",
+                        "description": "Baseline label from the manifest's [baselines] table (e.g. '0.17')"
+                    },
+                    "crate": {
+                        "type": "string",
+                        "description": "Limit the diff to paths containing this crate name or alias"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum entries per section (default 50, capped by server limits)"
+                    }
+                },
+                "required": ["baseline"]
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let toolkit = self.toolkit.current();
+        let request: ApiDiffRequest = super::parse_args(args)?;
+        let crate_filter = request.crate_name.as_deref();
+        let limit =
+            super::limits::result_limit(request.limit, 50, &toolkit.manifest().server.limits)?;
+
+        let old = match toolkit.baseline(&request.baseline).await {
+            Ok(index) => index,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
+        };
+        let diff = diff::diff_indexes(&old, toolkit.index());
+
+        let in_scope =
+            |item: &ApiItem| crate_filter.is_none_or(|name| item.full_path.contains(name));
+
+        let added: Vec<&ApiItem> = diff.added.into_iter().filter(|i| in_scope(i)).collect();
+        let removed: Vec<&ApiItem> = diff.removed.into_iter().filter(|i| in_scope(i)).collect();
+        let changed: Vec<ChangedItem> = diff
+            .changed
+            .into_iter()
+            .filter(|c| in_scope(c.new))
+            .collect();
+
+        super::respond(&ApiDiffResponse {
+            baseline: &request.baseline,
+            current_version: &toolkit.manifest().library.version,
+            summary: DiffSummary {
+                added: added.len(),
+                removed: removed.len(),
+                changed: changed.len(),
+            },
+            added: added.iter().take(limit).map(|i| diff_item(i)).collect(),
+            removed: removed.iter().take(limit).map(|i| diff_item(i)).collect(),
+            changed: changed
+                .iter()
+                .take(limit)
+                .map(|c| ChangedSignature {
+                    full_path: &c.new.full_path,
+                    kind: kind_label(&c.new.kind),
+                    old_signature: &c.old.signature,
+                    new_signature: &c.new.signature,
+                })
+                .collect(),
+        })
+    }
+}
+
+fn diff_item(item: &ApiItem) -> DiffItem<'_> {
+    DiffItem {
+        full_path: &item.full_path,
+        kind: kind_label(&item.kind),
+        signature: &item.signature,
+    }
+}
@@ -1,19 +1,49 @@
-use super::SharedState;
 use crate::parser::display;
-use crate::parser::index::ItemKind;
+use crate::toolkit::{kind_label, AmariToolkit, SearchFilter};
 use async_trait::async_trait;
 use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
 pub struct ApiSearchHandler {
-    pub state: Arc<SharedState>,
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiSearchRequest {
+    pub query: String,
+    pub kind: Option<String>,
+    #[serde(rename = "crate")]
+    pub crate_name: Option<String>,
+    pub limit: Option<u64>,
+    pub workspace: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiSearchResponse<'a> {
+    pub results: Vec<SearchResult<'a>>,
+    pub total_matches: usize,
+    pub query: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult<'a> {
+    pub name: &'a str,
+    pub kind: &'static str,
+    pub full_path: &'a str,
+    pub signature: &'a str,
+    pub doc_summary: String,
+    pub feature_gate: Option<&'a str>,
+    pub source_file: String,
+    pub line: usize,
 }
 
 #[async_trait]
 impl ToolHandler for ApiSearchHandler {
     fn metadata(&self) -> Option<pmcp::ToolInfo> {
-        Some(super::tool_info(
+        Some(super::library_tool_info(
+            &self.toolkit,
             "api_search",
             "Search the library API for types, functions, traits, and more by name",
             json!({
@@ -34,8 +64,9 @@ impl ToolHandler for ApiSearchHandler {
                     },
                     "limit": {
                         "type": "integer",
-                        "description": "Maximum number of results (default 20)"
-                    }
+                        "description": "Maximum number of results (default 20, capped by server limits)"
+                    },
+                    "workspace": super::workspace_property()
                 },
                 "required": ["query"]
             }),
@@ -43,99 +74,86 @@ impl ToolHandler for ApiSearchHandler {
     }
 
     async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
-        let query = args["query"]
-            .as_str()
-            .ok_or_else(|| McpError::invalid_params("query is required"))?;
+        let request: ApiSearchRequest = super::parse_args(args)?;
+        super::limits::check_query(
+            "query",
+            &request.query,
+            &self.toolkit.manifest().server.limits,
+        )?;
+        let toolkit = match self.toolkit.workspace(request.workspace.as_deref()).await {
+            Ok(toolkit) => toolkit,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
+        };
+        let limits = &toolkit.manifest().server.limits;
 
-        let kind_filter = args.get("kind").and_then(|v| v.as_str());
-        let crate_filter = args.get("crate").and_then(|v| v.as_str());
-        let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+        let filter = SearchFilter {
+            kind: request.kind.as_deref(),
+            crate_name: request.crate_name.as_deref(),
+            limit: None,
+        };
+        let limit = super::limits::result_limit(request.limit, 20, limits)?;
 
-        let results: Vec<Value> = self
-            .state
-            .index
-            .search(query)
+        let matches = toolkit.search(&request.query, &filter);
+        let total_matches = matches.len();
+        let results: Vec<SearchResult> = matches
             .into_iter()
-            .filter(|item| kind_filter.is_none_or(|kind| matches_kind(&item.kind, kind)))
-            .filter(|item| {
-                crate_filter.is_none_or(|crate_name| item.full_path.contains(crate_name))
-            })
             .take(limit)
-            .map(|item| {
-                json!({
-                    "name": item.name,
-                    "kind": kind_label(&item.kind),
-                    "full_path": item.full_path,
-                    "signature": item.signature,
-                    "doc_summary": display::first_sentence(&item.doc_comment),
-                    "feature_gate": item.feature_gate,
-                    "source_file": item.source_file.display().to_string(),
-                    "line": item.line_number,
-                })
+            .map(|item| SearchResult {
+                name: &item.name,
+                kind: kind_label(&item.kind),
+                full_path: &item.full_path,
+                signature: &item.signature,
+                doc_summary: display::first_sentence(&item.doc_comment),
+                feature_gate: item.feature_gate.as_deref(),
+                source_file: item.source_file.display().to_string(),
+                line: item.line_number,
             })
             .collect();
 
-        let total = results.len();
-        Ok(json!({
-            "results": results,
-            "total_matches": total,
-            "query": query,
-        }))
-    }
-}
-
-fn matches_kind(kind: &ItemKind, filter: &str) -> bool {
-    matches!(
-        (kind, filter),
-        (ItemKind::Function { .. }, "function")
-            | (ItemKind::Struct { .. }, "struct")
-            | (ItemKind::Enum { .. }, "enum")
-            | (ItemKind::Trait { .. }, "trait")
-            | (ItemKind::TypeAlias, "type")
-            | (ItemKind::Const { .. }, "const")
-            | (ItemKind::Impl { .. }, "impl")
-            | (ItemKind::ReExport { .. }, "reexport")
-    )
-}
-
-pub fn kind_label(kind: &ItemKind) -> &'static str {
-    match kind {
-        ItemKind::Function { .. } => "function",
-        ItemKind::Struct { .. } => "struct",
-        ItemKind::Enum { .. } => "enum",
-        ItemKind::Trait { .. } => "trait",
-        ItemKind::TypeAlias => "type",
-        ItemKind::Const { .. } => "const",
-        ItemKind::Impl { .. } => "impl",
-        ItemKind::ReExport { .. } => "reexport",
+        super::respond(&ApiSearchResponse {
+            total_matches,
+            results,
+            query: &request.query,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::registry::local_extra;
+    use std::path::Path;
 
-    #[test]
-    fn kind_filter_matches_correctly() {
-        assert!(matches_kind(
-            &ItemKind::Function {
-                is_async: false,
-                is_unsafe: false
-            },
-            "function"
-        ));
-        assert!(!matches_kind(
-            &ItemKind::Function {
-                is_async: false,
-                is_unsafe: false
-            },
-            "struct"
-        ));
-        assert!(matches_kind(
-            &ItemKind::Struct {
-                fields: crate::parser::index::FieldKind::Unit
-            },
-            "struct"
-        ));
+    fn handler() -> ApiSearchHandler {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/minilib.toml");
+        ApiSearchHandler {
+            toolkit: Arc::new(AmariToolkit::load(&manifest).expect("fixture should index")),
+        }
+    }
+
+    #[tokio::test]
+    async fn workspace_searches_that_library() {
+        let handler = handler();
+        let search = |args: Value| handler.handle(args, local_extra());
+
+        // `Point::distance` only exists in the primary library.
+        let primary = search(json!({"query": "distance"})).await.unwrap();
+        assert!(primary["total_matches"].as_u64().unwrap() > 0);
+        let fork = search(json!({"query": "distance", "workspace": "fork"}))
+            .await
+            .unwrap();
+        assert_eq!(fork["total_matches"], 0);
+
+        let points = search(json!({"query": "Point", "workspace": "fork"}))
+            .await
+            .unwrap();
+        let files: Vec<&str> = points["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["source_file"].as_str().unwrap())
+            .collect();
+        assert!(!files.is_empty());
+        assert!(files.iter().all(|f| f.contains("minilib-0.1")), "{files:?}");
     }
 }
@@ -0,0 +1,239 @@
+use super::registry::{AbortOnDrop, ToolRegistry};
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Default number of calls executed concurrently.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Upper bound on the per-request `concurrency` argument.
+pub const MAX_CONCURRENCY: usize = 16;
+
+pub struct BatchCallHandler {
+    pub tools: Arc<ToolRegistry>,
+    /// Most calls accepted in one batch (`[server.limits] max_batch_calls`).
+    pub max_calls: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchCallRequest {
+    /// Raw entries; checked by [`parse_calls`] so errors can name the index.
+    pub calls: Vec<Value>,
+    pub concurrency: Option<u64>,
+}
+
+/// A single entry of a batch request.
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    pub tool: String,
+    pub arguments: Value,
+}
+
+#[async_trait]
+impl ToolHandler for BatchCallHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::tool_info(
+            "batch_call",
+            "Execute several independent tool calls in one request; results are returned in input order",
+            json!({
+                "type": "object",
+                "properties": {
+                    "calls": {
+                        "type": "array",
+                        "description": "Tool calls to execute",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "tool": {
+                                    "type": "string",
+                                    "description": "Name of the tool to call"
+                                },
+                                "arguments": {
+                                    "type": "object",
+                                    "description": "Arguments passed to the tool"
+                                }
+                            },
+                            "required": ["tool"]
+                        }
+                    },
+                    "concurrency": {
+                        "type": "integer",
+                        "description": "Maximum calls executed at once (default 4, max 16)"
+                    }
+                },
+                "required": ["calls"]
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let request: BatchCallRequest = super::parse_args(args)?;
+        let calls =
+            parse_calls(&request.calls, self.max_calls).map_err(McpError::invalid_params)?;
+        let concurrency = request
+            .concurrency
+            .map(|n| (n as usize).clamp(1, MAX_CONCURRENCY))
+            .unwrap_or(DEFAULT_CONCURRENCY);
+
+        // Sub-calls are aborted if the batch is cancelled or dropped.
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        let handles: Vec<_> = calls
+            .iter()
+            .cloned()
+            .map(|entry| {
+                let tools = self.tools.clone();
+                let semaphore = semaphore.clone();
+                let extra = extra.clone();
+                AbortOnDrop(tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    tools.call(&entry.tool, entry.arguments, extra).await
+                }))
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(calls.len());
+        let mut failed = 0;
+        for (index, (entry, mut handle)) in calls.iter().zip(handles).enumerate() {
+            let outcome = match (&mut handle.0).await {
+                Ok(outcome) => outcome.map_err(|e| e.to_string()),
+                Err(join_error) => Err(format!("Tool call aborted: {join_error}")),
+            };
+            results.push(match outcome {
+                Ok(result) => json!({
+                    "index": index,
+                    "tool": entry.tool,
+                    "result": result,
+                }),
+                Err(error) => {
+                    failed += 1;
+                    json!({
+                        "index": index,
+                        "tool": entry.tool,
+                        "error": error,
+                    })
+                }
+            });
+        }
+
+        Ok(json!({
+            "results": results,
+            "total_calls": calls.len(),
+            "failed": failed,
+        }))
+    }
+}
+
+/// Validate the `calls` argument and turn it into batch entries.
+fn parse_calls(calls: &[Value], max_calls: usize) -> Result<Vec<BatchEntry>, String> {
+    if calls.is_empty() {
+        return Err("calls must contain at least one entry".to_string());
+    }
+    if calls.len() > max_calls {
+        return Err(format!(
+            "batch contains {} calls, maximum is {max_calls}",
+            calls.len()
+        ));
+    }
+
+    calls
+        .iter()
+        .enumerate()
+        .map(|(index, call)| {
+            let tool = call
+                .get("tool")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("calls[{index}].tool is required"))?;
+            if tool == "batch_call" {
+                return Err(format!("calls[{index}]: batch_call cannot be nested"));
+            }
+            Ok(BatchEntry {
+                tool: tool.to_string(),
+                arguments: call.get("arguments").cloned().unwrap_or_else(|| json!({})),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::registry::local_extra;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn parses_calls_with_default_arguments() {
+        let entries = [
+            json!({"tool": "api_search", "arguments": {"query": "Rotor"}}),
+            json!({"tool": "feature_map"}),
+        ];
+        let calls = parse_calls(&entries, 10).unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].tool, "api_search");
+        assert_eq!(calls[0].arguments["query"], "Rotor");
+        assert_eq!(calls[1].arguments, json!({}));
+    }
+
+    #[test]
+    fn rejects_missing_tool_name() {
+        let err = parse_calls(&[json!({"arguments": {}})], 10).unwrap_err();
+        assert!(err.contains("calls[0].tool"));
+    }
+
+    #[test]
+    fn rejects_nested_batches_and_empty_batches() {
+        assert!(parse_calls(&[], 10).is_err());
+        assert!(parse_calls(&[json!({"tool": "batch_call"})], 10).is_err());
+    }
+
+    /// Sets `finished` only if allowed to run for its full delay.
+    struct SlowHandler {
+        finished: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl ToolHandler for SlowHandler {
+        async fn handle(
+            &self,
+            _args: Value,
+            _extra: RequestHandlerExtra,
+        ) -> Result<Value, McpError> {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            self.finished.store(true, Ordering::SeqCst);
+            Ok(json!({}))
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelling_the_batch_aborts_sub_calls() {
+        let finished = Arc::new(AtomicBool::new(false));
+        let mut tools = ToolRegistry::new();
+        tools.register(
+            "slow",
+            SlowHandler {
+                finished: finished.clone(),
+            },
+        );
+        let batch = BatchCallHandler {
+            tools: Arc::new(tools),
+            max_calls: 10,
+        };
+
+        let call = batch.handle(json!({"calls": [{"tool": "slow"}]}), local_extra());
+        assert!(tokio::time::timeout(Duration::from_millis(20), call)
+            .await
+            .is_err());
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert!(!finished.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn rejects_oversized_batches() {
+        let calls: Vec<Value> = (0..3).map(|_| json!({"tool": "feature_map"})).collect();
+        assert!(parse_calls(&calls, 3).is_ok());
+        assert!(parse_calls(&calls, 2).is_err());
+    }
+}
@@ -1,17 +1,55 @@
-use super::SharedState;
+use crate::toolkit::AmariToolkit;
 use async_trait::async_trait;
 use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
 pub struct BrowseDocsHandler {
-    pub state: Arc<SharedState>,
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BrowseDocsRequest {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub module: Option<String>,
+    pub item: Option<String>,
+    pub workspace: Option<String>,
+}
+
+/// Documentation for whichever of item, module, or crate was requested.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum BrowseDocsResponse<'a> {
+    Item {
+        item: &'a str,
+        full_path: &'a str,
+        signature: &'a str,
+        documentation: &'a str,
+        source_file: String,
+        line: usize,
+    },
+    Module {
+        #[serde(rename = "crate")]
+        crate_name: &'a str,
+        module: &'a str,
+        documentation: &'a str,
+    },
+    Crate {
+        #[serde(rename = "crate")]
+        crate_name: &'a str,
+        alias: Option<&'a str>,
+        feature_gate: Option<&'a str>,
+        documentation: &'a str,
+    },
 }
 
 #[async_trait]
 impl ToolHandler for BrowseDocsHandler {
     fn metadata(&self) -> Option<pmcp::ToolInfo> {
-        Some(super::tool_info(
+        Some(super::library_tool_info(
+            &self.toolkit,
             "browse_docs",
             "Browse module-level and item-level documentation from the library source",
             json!({
@@ -28,7 +66,8 @@ impl ToolHandler for BrowseDocsHandler {
                     "item": {
                         "type": "string",
                         "description": "Specific item name for full docs"
-                    }
+                    },
+                    "workspace": super::workspace_property()
                 },
                 "required": ["crate"]
             }),
@@ -36,87 +75,46 @@ impl ToolHandler for BrowseDocsHandler {
     }
 
     async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
-        let crate_name = args["crate"]
-            .as_str()
-            .ok_or_else(|| McpError::invalid_params("crate is required"))?;
-
-        let module_path = args.get("module").and_then(|v| v.as_str());
-        let item_name = args.get("item").and_then(|v| v.as_str());
-
-        let crate_info = self.state.index.get_crate(crate_name);
-        let Some(crate_info) = crate_info else {
-            return Ok(json!({"error": format!("Crate '{crate_name}' not found")}));
+        let request: BrowseDocsRequest = super::parse_args(args)?;
+        let toolkit = match self.toolkit.workspace(request.workspace.as_deref()).await {
+            Ok(toolkit) => toolkit,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
         };
+        let crate_name = request.crate_name.as_str();
 
-        if let Some(item) = item_name {
-            let found = self
-                .state
-                .index
-                .search(item)
-                .into_iter()
-                .find(|i| i.name == item && i.full_path.contains(crate_name));
-
-            return if let Some(found) = found {
-                Ok(json!({
-                    "item": found.name,
-                    "full_path": found.full_path,
-                    "signature": found.signature,
-                    "documentation": found.doc_comment,
-                    "source_file": found.source_file.display().to_string(),
-                    "line": found.line_number,
-                }))
-            } else {
-                Ok(json!({"error": format!("Item '{item}' not found in crate '{crate_name}'")}))
-            };
-        }
-
-        if let Some(path) = module_path {
-            find_module_docs(&crate_info.modules, path)
-                .map(|(name, docs)| {
-                    Ok(json!({
-                        "crate": crate_name,
-                        "module": name,
-                        "documentation": docs,
-                    }))
+        let response = if let Some(item) = request.item.as_deref() {
+            toolkit
+                .item_docs(crate_name, item)
+                .map(|found| BrowseDocsResponse::Item {
+                    item: &found.name,
+                    full_path: &found.full_path,
+                    signature: &found.signature,
+                    documentation: &found.doc_comment,
+                    source_file: found.source_file.display().to_string(),
+                    line: found.line_number,
                 })
-                .unwrap_or_else(|| {
-                    Ok(json!({
-                        "error": format!("Module '{path}' not found in crate '{crate_name}'"),
-                    }))
+        } else if let Some(path) = request.module.as_deref() {
+            toolkit
+                .module(crate_name, Some(path))
+                .map(|view| BrowseDocsResponse::Module {
+                    crate_name,
+                    module: &view.module.name,
+                    documentation: &view.module.module_docs,
                 })
         } else {
-            Ok(json!({
-                "crate": crate_name,
-                "alias": crate_info.alias,
-                "feature_gate": crate_info.feature_gate,
-                "documentation": crate_info.module_docs,
-            }))
-        }
-    }
-}
-
-fn find_module_docs(
-    modules: &[crate::parser::index::ModuleInfo],
-    path: &str,
-) -> Option<(String, String)> {
-    let parts: Vec<&str> = path.split("::").collect();
-    find_docs_recursive(modules, &parts)
-}
+            toolkit
+                .crate_info(crate_name)
+                .map(|crate_info| BrowseDocsResponse::Crate {
+                    crate_name,
+                    alias: crate_info.alias.as_deref(),
+                    feature_gate: crate_info.feature_gate.as_deref(),
+                    documentation: &crate_info.module_docs,
+                })
+        };
 
-fn find_docs_recursive(
-    modules: &[crate::parser::index::ModuleInfo],
-    parts: &[&str],
-) -> Option<(String, String)> {
-    if parts.is_empty() {
-        return None;
-    }
-    for module in modules {
-        if module.name == parts[0] {
-            if parts.len() == 1 {
-                return Some((module.name.clone(), module.module_docs.clone()));
-            }
-            return find_docs_recursive(&module.submodules, &parts[1..]);
+        match response {
+            Ok(response) => super::respond(&response),
+            Err(e) => Ok(json!({"error": e.to_string()})),
         }
     }
-    None
 }
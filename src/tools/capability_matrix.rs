@@ -0,0 +1,149 @@
+use super::catalog::{self, Implementation};
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+pub struct CapabilityMatrixHandler {
+    /// Names of every tool registered with the server, including this one.
+    pub tools: Vec<String>,
+    /// Statuses reported by plugins, by prefixed tool name.
+    pub plugins: HashMap<String, Implementation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CapabilityMatrixRequest {
+    /// Only list tools with this status label.
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CapabilityMatrixResponse<'a> {
+    /// Registered tools per status, before the `status` filter.
+    pub summary: BTreeMap<&'static str, usize>,
+    pub tools: Vec<ToolCapability<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolCapability<'a> {
+    pub name: &'a str,
+    /// `real`, `partial`, `stub`, or `unreported` for plugin tools that
+    /// don't report one.
+    pub implementation_status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<&'static str>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub tags: &'static [&'static str],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub limitations: &'static [&'static str],
+}
+
+const UNREPORTED: &str = "unreported";
+
+/// Values accepted by the `status` filter.
+const STATUSES: [&str; 4] = ["real", "partial", "stub", UNREPORTED];
+
+#[async_trait]
+impl ToolHandler for CapabilityMatrixHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::tool_info(
+            "capability_matrix",
+            "List every registered tool with its implementation status (real, partial, stub) and known limitations, so results from partial or stub tools aren't presented as authoritative",
+            json!({
+                "type": "object",
+                "properties": {
+                    "status": {
+                        "type": "string",
+                        "enum": STATUSES,
+                        "description": "Only list tools with this status"
+                    }
+                }
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let request: CapabilityMatrixRequest = super::parse_args(args)?;
+        if let Some(status) = request.status.as_deref() {
+            if !STATUSES.contains(&status) {
+                return Err(McpError::invalid_params(format!(
+                    "status must be one of {}, got '{status}'",
+                    STATUSES.join(", ")
+                )));
+            }
+        }
+
+        let mut names: Vec<&str> = self.tools.iter().map(String::as_str).collect();
+        names.sort_unstable();
+        let capabilities: Vec<ToolCapability> = names
+            .into_iter()
+            .map(|name| capability(name, &self.plugins))
+            .collect();
+
+        let mut summary = BTreeMap::new();
+        for tool in &capabilities {
+            *summary.entry(tool.implementation_status).or_default() += 1;
+        }
+        super::respond(&CapabilityMatrixResponse {
+            summary,
+            tools: capabilities
+                .into_iter()
+                .filter(|tool| {
+                    request
+                        .status
+                        .as_deref()
+                        .is_none_or(|status| tool.implementation_status == status)
+                })
+                .collect(),
+        })
+    }
+}
+
+fn capability<'a>(name: &'a str, plugins: &HashMap<String, Implementation>) -> ToolCapability<'a> {
+    let hints = catalog::hints(name);
+    let implementation = catalog::implementation(name).or_else(|| plugins.get(name).copied());
+    ToolCapability {
+        name,
+        implementation_status: implementation.map_or(UNREPORTED, |i| i.status.label()),
+        cost: hints.map(|h| h.cost.label()),
+        tags: hints.map(|h| h.tags).unwrap_or_default(),
+        limitations: implementation.map(|i| i.limitations).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::catalog::Status;
+
+    #[test]
+    fn status_filter_matches_catalog_labels() {
+        for status in [Status::Real, Status::Partial, Status::Stub] {
+            assert!(STATUSES.contains(&status.label()));
+        }
+    }
+
+    #[test]
+    fn plugin_tools_report_their_own_status() {
+        let mut plugins = HashMap::new();
+        let tool = capability("geo_rotate", &plugins);
+        assert_eq!(tool.implementation_status, UNREPORTED);
+        assert!(tool.cost.is_none());
+
+        plugins.insert(
+            "geo_rotate".to_string(),
+            Implementation {
+                status: Status::Stub,
+                limitations: &["Returns a fixed rotor"],
+            },
+        );
+        let tool = capability("geo_rotate", &plugins);
+        assert_eq!(tool.implementation_status, "stub");
+        assert_eq!(tool.limitations, ["Returns a fixed rotor"]);
+
+        let tool = capability("upgrade_advice", &plugins);
+        assert_eq!(tool.implementation_status, "partial");
+        assert!(!tool.limitations.is_empty());
+    }
+}
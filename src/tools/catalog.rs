@@ -0,0 +1,489 @@
+//! Discovery metadata appended to each tool's MCP description: domain tags,
+//! a cost hint, an implementation status, and example invocations, so
+//! clients can pick the right tool from `tools/list` alone.
+//!
+//! Examples are templates: `<type>`, `<crate>` and the other tokens in
+//! [`ExampleValues`] are filled from the served library, so the published
+//! calls work for whatever library the manifest describes.
+
+use crate::parser::index::{ItemKind, ModuleInfo};
+use crate::toolkit::AmariToolkit;
+
+/// Rough cost of a call, for clients deciding between tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cost {
+    /// Answered from the in-memory index.
+    Cheap,
+    /// Reads source files or walks most of the index.
+    Moderate,
+    /// May parse another checkout or run many other tools.
+    Expensive,
+}
+
+impl Cost {
+    pub fn label(self) -> &'static str {
+        match self {
+            Cost::Cheap => "cheap",
+            Cost::Moderate => "moderate",
+            Cost::Expensive => "expensive",
+        }
+    }
+}
+
+/// How completely a tool does what its description says, so agents know
+/// which results to present as authoritative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Answers fully from the index, the checkout, or the server's own state.
+    Real,
+    /// Real output with known gaps, listed in its limitations.
+    Partial,
+    /// Placeholder output. No built-in tool is a stub; plugins report it
+    /// through [`crate::tools::plugin::ToolPlugin::implementation`].
+    Stub,
+}
+
+impl Status {
+    pub fn label(self) -> &'static str {
+        match self {
+            Status::Real => "real",
+            Status::Partial => "partial",
+            Status::Stub => "stub",
+        }
+    }
+}
+
+/// A tool's [`Status`] and, unless real, what it doesn't cover.
+#[derive(Debug, Clone, Copy)]
+pub struct Implementation {
+    pub status: Status,
+    pub limitations: &'static [&'static str],
+}
+
+/// Implementation status of a built-in tool; `None` for plugin and unknown
+/// tools, which report nothing.
+pub fn implementation(tool: &str) -> Option<Implementation> {
+    hints(tool)?;
+    let limitations: &[&str] = match tool {
+        "search_docs" => &[
+            "Keyword mode matches exact words; there is no stemming",
+            "Semantic mode uses a bag-of-words model with no synonyms unless an embedding provider is configured",
+        ],
+        "explain_error" => &[
+            "Causes and fixes come from a fixed table of common rustc errors; other errors only get index lookups",
+        ],
+        "upgrade_advice" => &[
+            "Only paths written out in the project are checked; method calls and glob-imported names are not",
+        ],
+        _ => &[],
+    };
+    Some(Implementation {
+        status: if limitations.is_empty() {
+            Status::Real
+        } else {
+            Status::Partial
+        },
+        limitations,
+    })
+}
+
+/// Discovery metadata for one tool.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolHints {
+    pub tags: &'static [&'static str],
+    pub cost: Cost,
+    /// Example argument objects, as JSON templates; see [`fill`].
+    pub examples: &'static [&'static str],
+}
+
+/// Names from the served library substituted for example tokens. A token
+/// without a value (no baselines configured, no optional features) leaves
+/// out the examples that use it.
+#[derive(Debug, Clone, Default)]
+pub struct ExampleValues {
+    /// `<crate>`: alias of the first indexed default crate, else its name.
+    pub crate_alias: Option<String>,
+    /// `<crate_name>`: that crate's name.
+    pub crate_name: Option<String>,
+    /// `<module>`: path of the crate's first submodule, e.g. `lib::shapes`.
+    pub module: Option<String>,
+    /// `<type>`: the crate's first struct or enum.
+    pub type_name: Option<String>,
+    /// `<function>`: the crate's first function.
+    pub function: Option<String>,
+    /// `<feature>`: first optional crate feature, by name.
+    pub feature: Option<String>,
+    /// `<baseline>`: first `[baselines]` label.
+    pub baseline: Option<String>,
+    /// `<version>`: the library version.
+    pub version: Option<String>,
+    /// `<project>`: first `[server] project_roots` entry.
+    pub project: Option<String>,
+}
+
+impl ExampleValues {
+    pub fn from_toolkit(toolkit: &AmariToolkit) -> Self {
+        let manifest = toolkit.manifest();
+        let crates = &toolkit.index().crates;
+        let first_crate = crates
+            .iter()
+            .find(|c| c.feature_gate.is_none())
+            .or_else(|| crates.first());
+        let first = |matches: fn(&ItemKind) -> bool| {
+            first_crate.and_then(|c| first_item(&c.modules, matches))
+        };
+        let first_key = |map: &std::collections::HashMap<String, String>| map.keys().min().cloned();
+        Self {
+            crate_alias: first_crate.map(|c| c.alias.as_ref().unwrap_or(&c.name).clone()),
+            crate_name: first_crate.map(|c| c.name.clone()),
+            module: first_crate
+                .and_then(|c| c.modules.first())
+                .and_then(|root| {
+                    let sub = root.submodules.first()?;
+                    Some(format!("{}::{}", root.name, sub.name))
+                }),
+            type_name: first(|kind| {
+                matches!(kind, ItemKind::Struct { .. } | ItemKind::Enum { .. })
+            }),
+            function: first(|kind| matches!(kind, ItemKind::Function { .. })),
+            feature: first_key(&manifest.crates.optional),
+            baseline: first_key(&manifest.baselines),
+            version: Some(manifest.library.version.clone()),
+            project: manifest
+                .resolve_project_roots()
+                .first()
+                .map(|root| root.display().to_string()),
+        }
+    }
+
+    fn get(&self, token: &str) -> Option<&str> {
+        match token {
+            "crate" => self.crate_alias.as_deref(),
+            "crate_name" => self.crate_name.as_deref(),
+            "module" => self.module.as_deref(),
+            "type" => self.type_name.as_deref(),
+            "function" => self.function.as_deref(),
+            "feature" => self.feature.as_deref(),
+            "baseline" => self.baseline.as_deref(),
+            "version" => self.version.as_deref(),
+            "project" => self.project.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+const TOKENS: &[&str] = &[
+    "crate_name",
+    "crate",
+    "module",
+    "type",
+    "function",
+    "feature",
+    "baseline",
+    "version",
+    "project",
+];
+
+/// Name of the first item, depth first, whose kind `matches`.
+fn first_item(modules: &[ModuleInfo], matches: fn(&ItemKind) -> bool) -> Option<String> {
+    modules.iter().find_map(|module| {
+        module
+            .items
+            .iter()
+            .find(|item| matches(&item.kind))
+            .map(|item| item.name.clone())
+            .or_else(|| first_item(&module.submodules, matches))
+    })
+}
+
+/// `template` with each `<token>` replaced by its JSON-escaped value, or
+/// `None` if a token it uses has no value.
+pub fn fill(template: &str, values: &ExampleValues) -> Option<String> {
+    let mut text = template.to_string();
+    for token in TOKENS {
+        let placeholder = format!("<{token}>");
+        if text.contains(&placeholder) {
+            let escaped = serde_json::to_string(values.get(token)?).ok()?;
+            text = text.replace(&placeholder, &escaped[1..escaped.len() - 1]);
+        }
+    }
+    Some(text)
+}
+
+/// Hints for a tool by name.
+pub fn hints(tool: &str) -> Option<ToolHints> {
+    let (tags, cost, examples): (&[&str], Cost, &[&str]) = match tool {
+        "api_search" => (
+            &["search", "types", "functions"],
+            Cost::Cheap,
+            &[
+                r#"{"query": "<type>"}"#,
+                r#"{"query": "<function>", "kind": "function", "crate": "<crate>"}"#,
+            ],
+        ),
+        "type_info" => (
+            &["types", "signatures"],
+            Cost::Cheap,
+            &[r#"{"name": "<type>"}"#],
+        ),
+        "module_overview" => (
+            &["modules", "navigation"],
+            Cost::Cheap,
+            &[
+                r#"{"crate": "<crate>"}"#,
+                r#"{"crate": "<crate>", "module": "<module>"}"#,
+            ],
+        ),
+        "feature_map" => (
+            &["features", "crates"],
+            Cost::Cheap,
+            &[r#"{}"#, r#"{"feature": "<feature>"}"#],
+        ),
+        "dependency_graph" => (
+            &["crates", "dependencies"],
+            Cost::Moderate,
+            &[r#"{"crate": "<crate_name>"}"#, r#"{"render": "mermaid"}"#],
+        ),
+        "browse_docs" => (
+            &["documentation"],
+            Cost::Cheap,
+            &[
+                r#"{"crate": "<crate>"}"#,
+                r#"{"crate": "<crate>", "item": "<type>"}"#,
+            ],
+        ),
+        "usage_examples" => (
+            &["documentation", "examples"],
+            Cost::Moderate,
+            &[r#"{"name": "<type>"}"#],
+        ),
+        "resolve_features" => (
+            &["features", "crates", "setup"],
+            Cost::Moderate,
+            &[
+                r#"{"capabilities": ["<feature>", "no_std"]}"#,
+                r#"{"capabilities": ["no_std"]}"#,
+            ],
+        ),
+        "explain_error" => (
+            &["errors", "troubleshooting"],
+            Cost::Cheap,
+            &[r#"{"message": "error[E0412]: cannot find type `<type>` in this scope"}"#],
+        ),
+        "search_docs" => (
+            &["discovery", "documentation"],
+            Cost::Moderate,
+            &[
+                r#"{"query": "<type>"}"#,
+                r#"{"query": "how do I create a <type>", "mode": "semantic"}"#,
+            ],
+        ),
+        "changelog" => (
+            &["versions", "migration"],
+            Cost::Moderate,
+            &[r#"{"to": "<version>"}"#, r#"{"from": "<version>"}"#],
+        ),
+        "api_diff" => (
+            &["versions", "migration"],
+            Cost::Expensive,
+            &[r#"{"baseline": "<baseline>", "crate": "<crate_name>"}"#],
+        ),
+        "upgrade_advice" => (
+            &["versions", "migration"],
+            Cost::Expensive,
+            &[r#"{"path": "<project>"}"#],
+        ),
+        "batch_call" => (
+            &["composite"],
+            Cost::Expensive,
+            &[r#"{"calls": [{"tool": "server_info"}, {"tool": "feature_map"}]}"#],
+        ),
+        "record_session" => (&["session"], Cost::Cheap, &[r#"{}"#]),
+        "replay_script" => (
+            &["session", "composite"],
+            Cost::Expensive,
+            &[r#"{"script": {"version": 1, "calls": [{"tool": "feature_map"}]}}"#],
+        ),
+        "usage_stats" => (
+            &["server", "diagnostics"],
+            Cost::Cheap,
+            &[r#"{"tool": "api_search"}"#],
+        ),
+        "server_info" => (&["server", "diagnostics"], Cost::Cheap, &[r#"{}"#]),
+        "capability_matrix" => (
+            &["server", "diagnostics"],
+            Cost::Cheap,
+            &[r#"{}"#, r#"{"status": "partial"}"#],
+        ),
+        _ => return None,
+    };
+    Some(ToolHints {
+        tags,
+        cost,
+        examples,
+    })
+}
+
+/// `description` followed by the tool's tags, cost, status, and the
+/// examples `values` can fill.
+pub fn describe(tool: &str, description: &str, values: &ExampleValues) -> String {
+    let (Some(hints), Some(implementation)) = (hints(tool), implementation(tool)) else {
+        return description.to_string();
+    };
+    let mut text = format!(
+        "{description}\n\nTags: {}. Cost: {}. Status: {}.",
+        hints.tags.join(", "),
+        hints.cost.label(),
+        implementation.status.label()
+    );
+    push_limitations(&mut text, implementation);
+    for example in hints.examples.iter().filter_map(|e| fill(e, values)) {
+        text.push_str(&format!("\nExample: {example}"));
+    }
+    text
+}
+
+/// `description` followed by a plugin tool's reported status and
+/// limitations; plugin tools have no tags, cost, or examples.
+pub fn describe_status(description: &str, implementation: Implementation) -> String {
+    let mut text = format!(
+        "{description}\n\nStatus: {}.",
+        implementation.status.label()
+    );
+    push_limitations(&mut text, implementation);
+    text
+}
+
+fn push_limitations(text: &mut String, implementation: Implementation) {
+    for limitation in implementation.limitations {
+        text.push_str(&format!("\nLimitation: {limitation}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn examples_are_valid_json_objects() {
+        let sample = ExampleValues {
+            crate_alias: Some("core".into()),
+            crate_name: Some("lib-core".into()),
+            module: Some("lib::shapes".into()),
+            type_name: Some("Shape".into()),
+            function: Some("area".into()),
+            feature: Some("extra".into()),
+            baseline: Some("0.1".into()),
+            version: Some("0.2.0".into()),
+            project: Some("/tmp/app".into()),
+        };
+        let tools = [
+            "api_search",
+            "type_info",
+            "module_overview",
+            "feature_map",
+            "dependency_graph",
+            "browse_docs",
+            "usage_examples",
+            "resolve_features",
+            "explain_error",
+            "search_docs",
+            "changelog",
+            "api_diff",
+            "upgrade_advice",
+            "batch_call",
+            "record_session",
+            "replay_script",
+            "usage_stats",
+            "server_info",
+            "capability_matrix",
+        ];
+        for tool in tools {
+            let hints = hints(tool).unwrap_or_else(|| panic!("no hints for {tool}"));
+            assert!(!hints.tags.is_empty());
+            for example in hints.examples {
+                let parsed: Value = serde_json::from_str(&fill(example, &sample).unwrap())
+                    .unwrap_or_else(|e| panic!("{tool} example {example} is invalid: {e}"));
+                assert!(parsed.is_object());
+            }
+        }
+    }
+
+    #[test]
+    fn describe_appends_hints() {
+        let values = ExampleValues {
+            type_name: Some("Widget".into()),
+            ..Default::default()
+        };
+        let text = describe("type_info", "Get type details", &values);
+        assert!(text.starts_with(
+            "Get type details\n\nTags: types, signatures. Cost: cheap. Status: real."
+        ));
+        assert!(text.ends_with("Example: {\"name\": \"Widget\"}"));
+        assert_eq!(describe("unknown", "Plain", &values), "Plain");
+    }
+
+    #[test]
+    fn examples_without_values_are_left_out() {
+        let values = ExampleValues {
+            crate_name: Some("minilib".into()),
+            ..Default::default()
+        };
+        let text = describe("api_diff", "Diff", &values);
+        assert!(!text.contains("Example:"), "{text}");
+        let text = describe("feature_map", "Features", &values);
+        assert!(text.ends_with("\nExample: {}"), "{text}");
+        assert_eq!(
+            fill(
+                r#"{"query": "<type>"}"#,
+                &ExampleValues {
+                    type_name: Some("a\"b".into()),
+                    ..Default::default()
+                }
+            ),
+            Some(r#"{"query": "a\"b"}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn example_values_come_from_the_served_library() {
+        let manifest =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/minilib.toml");
+        let toolkit = AmariToolkit::load(&manifest).expect("fixture should index");
+        let values = ExampleValues::from_toolkit(&toolkit);
+        assert_eq!(values.crate_name.as_deref(), Some("minilib-core"));
+        assert_eq!(values.crate_alias.as_deref(), Some("core"));
+        assert_eq!(values.baseline.as_deref(), Some("0.1"));
+        assert!(values.type_name.is_some());
+        for tool in ["api_search", "type_info", "module_overview", "api_diff"] {
+            let text = describe(tool, "", &values);
+            assert!(text.contains("Example:"), "{tool}: {text}");
+            assert!(!text.contains('<'), "{tool}: {text}");
+        }
+    }
+
+    #[test]
+    fn partial_tools_list_limitations() {
+        let upgrade = implementation("upgrade_advice").unwrap();
+        assert_eq!(upgrade.status, Status::Partial);
+        assert!(
+            describe("upgrade_advice", "Scan", &ExampleValues::default())
+                .contains("\nLimitation: Only paths")
+        );
+        assert_eq!(implementation("type_info").unwrap().status, Status::Real);
+        assert!(implementation("geo_rotate").is_none());
+    }
+
+    #[test]
+    fn describe_status_reports_plugin_stubs() {
+        let stub = Implementation {
+            status: Status::Stub,
+            limitations: &["Returns a fixed rotor"],
+        };
+        assert_eq!(
+            describe_status("Rotate", stub),
+            "Rotate\n\nStatus: stub.\nLimitation: Returns a fixed rotor"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::parser::changelog::{compare_versions, Release};
+use crate::toolkit::AmariToolkit;
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+pub struct ChangelogHandler {
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangelogRequest {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChangelogResponse<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<&'a str>,
+    pub has_changelog: bool,
+    /// Entry counts per section heading across all releases returned.
+    pub summary: BTreeMap<&'a str, usize>,
+    pub releases: Vec<ReleaseView<'a>>,
+    /// Tags in the range with no changelog section for their version.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags_without_notes: Vec<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReleaseView<'a> {
+    pub version: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<&'a str>,
+    /// Tag naming this version, if the checkout has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<&'a str>,
+    pub sections: Vec<SectionView<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SectionView<'a> {
+    pub heading: &'a str,
+    pub entries: &'a [String],
+}
+
+#[async_trait]
+impl ToolHandler for ChangelogHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::library_tool_info(
+            &self.toolkit,
+            "changelog",
+            "Release notes between two versions of the library, from the checkout's CHANGELOG.md and git tags, grouped by release and section (Added, Changed, Removed, ...)",
+            json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "Exclusive lower version, e.g. \"0.8\". Omit to start from the first release."
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Inclusive upper version, e.g. \"0.9\" (matches 0.9.0). Omit to include everything since `from`, unreleased changes too."
+                    }
+                }
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let request: ChangelogRequest = super::parse_args(args)?;
+        let limits = &self.toolkit.manifest().server.limits;
+        for (field, value) in [("from", &request.from), ("to", &request.to)] {
+            if let Some(value) = value {
+                super::limits::check_query(field, value, limits)?;
+            }
+        }
+
+        let notes = match self
+            .toolkit
+            .changelog(request.from.as_deref(), request.to.as_deref())
+        {
+            Ok(notes) => notes,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
+        };
+
+        let mut summary = BTreeMap::new();
+        for section in notes.releases.iter().flat_map(|r| &r.sections) {
+            *summary.entry(section.heading.as_str()).or_default() += section.entries.len();
+        }
+        let tag_for = |release: &Release| {
+            notes
+                .tags
+                .iter()
+                .find(|(_, version)| same_version(version, &release.version))
+                .map(|(tag, _)| tag.as_str())
+        };
+
+        super::respond(&ChangelogResponse {
+            from: request.from.as_deref(),
+            to: request.to.as_deref(),
+            has_changelog: notes.has_changelog,
+            summary,
+            releases: notes
+                .releases
+                .iter()
+                .map(|release| ReleaseView {
+                    version: &release.version,
+                    date: release.date.as_deref(),
+                    tag: tag_for(release),
+                    sections: release
+                        .sections
+                        .iter()
+                        .map(|s| SectionView {
+                            heading: &s.heading,
+                            entries: &s.entries,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            tags_without_notes: notes
+                .tags
+                .iter()
+                .filter(|(_, version)| {
+                    !notes
+                        .releases
+                        .iter()
+                        .any(|r| same_version(version, &r.version))
+                })
+                .map(|(tag, _)| tag.as_str())
+                .collect(),
+        })
+    }
+}
+
+fn same_version(a: &str, b: &str) -> bool {
+    compare_versions(a, b) == Some(Ordering::Equal)
+}
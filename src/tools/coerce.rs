@@ -0,0 +1,252 @@
+//! Lenient argument handling: nudges slightly-off arguments into the shape a
+//! tool's input schema declares, so near-miss calls succeed with a warning
+//! instead of failing outright.
+
+use super::validate::child_pointer;
+use serde_json::{Map, Value};
+
+/// Coerce `args` in place toward `schema`, returning one warning per change,
+/// each prefixed with the JSON pointer of the field it touched.
+///
+/// Handles the mistakes clients commonly make: numbers or booleans sent as
+/// strings, integral floats for integers, scalars where a string is
+/// expected, objects or arrays sent as JSON text, enum values in the wrong
+/// case, and explicit `null` for optional fields.
+pub fn coerce_args(args: &mut Value, schema: &Value) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if args.is_null() {
+        *args = Value::Object(Map::new());
+    }
+    coerce_value(args, schema, "", &mut warnings);
+    warnings
+}
+
+fn coerce_value(value: &mut Value, schema: &Value, pointer: &str, warnings: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type").and_then(|t| t.as_str()) {
+        if let Some(coerced) = coerce_scalar(value, expected) {
+            warnings.push(format!(
+                "{}: converted {} to {expected}",
+                display_pointer(pointer),
+                type_name(value)
+            ));
+            *value = coerced;
+        }
+    }
+
+    if let Some(option) = enum_match(value, schema) {
+        warnings.push(format!(
+            "{}: matched {value} to \"{option}\"",
+            display_pointer(pointer)
+        ));
+        *value = Value::String(option.to_string());
+    }
+
+    match value {
+        Value::Object(map) => {
+            let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+                return;
+            };
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(|r| r.as_array())
+                .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+
+            let nulls: Vec<String> = map
+                .iter()
+                .filter(|(key, v)| v.is_null() && !required.contains(&key.as_str()))
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in nulls {
+                map.remove(&key);
+                warnings.push(format!(
+                    "{}: dropped null for optional field",
+                    child_pointer(pointer, &key)
+                ));
+            }
+
+            for (key, field) in map.iter_mut() {
+                if let Some(field_schema) = properties.get(key) {
+                    coerce_value(field, field_schema, &child_pointer(pointer, key), warnings);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter_mut().enumerate() {
+                    coerce_value(item, item_schema, &format!("{pointer}/{index}"), warnings);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The value converted to the schema type, or `None` if it already has
+/// that type or can't be converted.
+fn coerce_scalar(value: &Value, expected: &str) -> Option<Value> {
+    match (expected, value) {
+        ("integer", Value::String(s)) => s.trim().parse::<i64>().ok().map(Value::from),
+        ("integer", Value::Number(n)) if n.is_f64() => n
+            .as_f64()
+            .filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64)
+            .map(|f| Value::from(f as i64)),
+        ("number", Value::String(s)) => s
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|f| f.is_finite())
+            .map(Value::from),
+        ("boolean", Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ("string", Value::Number(n)) => Some(Value::String(n.to_string())),
+        ("string", Value::Bool(b)) => Some(Value::String(b.to_string())),
+        ("string", Value::Array(items)) if items.len() == 1 && items[0].is_string() => {
+            Some(items[0].clone())
+        }
+        ("object", Value::String(s)) => serde_json::from_str::<Value>(s)
+            .ok()
+            .filter(Value::is_object),
+        ("array", Value::String(s)) => serde_json::from_str::<Value>(s)
+            .ok()
+            .filter(Value::is_array),
+        _ => None,
+    }
+}
+
+/// The enum option that `value` names in a different case, if any.
+fn enum_match<'a>(value: &Value, schema: &'a Value) -> Option<&'a str> {
+    let given = value.as_str()?;
+    schema
+        .get("enum")?
+        .as_array()?
+        .iter()
+        .filter_map(|option| option.as_str())
+        .find(|option| *option != given && option.eq_ignore_ascii_case(given))
+}
+
+fn display_pointer(pointer: &str) -> &str {
+    if pointer.is_empty() {
+        "/"
+    } else {
+        pointer
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string"},
+                "kind": {"type": "string", "enum": ["struct", "trait"]},
+                "limit": {"type": "integer"},
+                "exact": {"type": "boolean"},
+                "calls": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "tool": {"type": "string"},
+                            "arguments": {"type": "object"}
+                        }
+                    }
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    #[test]
+    fn well_formed_arguments_are_untouched() {
+        let mut args = json!({"query": "Rotor", "kind": "struct", "limit": 5});
+        let original = args.clone();
+        assert!(coerce_args(&mut args, &schema()).is_empty());
+        assert_eq!(args, original);
+    }
+
+    #[test]
+    fn converts_strings_to_declared_scalar_types() {
+        let mut args = json!({"query": 42, "limit": "20", "exact": "TRUE"});
+        let warnings = coerce_args(&mut args, &schema());
+        assert_eq!(args, json!({"query": "42", "limit": 20, "exact": true}));
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.contains(&"/limit: converted string to integer".to_string()));
+    }
+
+    #[test]
+    fn integral_floats_become_integers() {
+        let mut args = json!({"query": "x", "limit": 10.0});
+        coerce_args(&mut args, &schema());
+        assert_eq!(args["limit"], 10);
+
+        let mut args = json!({"query": "x", "limit": 10.5});
+        assert!(coerce_args(&mut args, &schema()).is_empty());
+    }
+
+    #[test]
+    fn enum_values_match_case_insensitively() {
+        let mut args = json!({"query": "x", "kind": "Struct"});
+        let warnings = coerce_args(&mut args, &schema());
+        assert_eq!(args["kind"], "struct");
+        assert_eq!(warnings, vec!["/kind: matched \"Struct\" to \"struct\""]);
+    }
+
+    #[test]
+    fn optional_nulls_are_dropped_and_required_kept() {
+        let mut args = json!({"query": null, "limit": null});
+        coerce_args(&mut args, &schema());
+        assert_eq!(args, json!({"query": null}));
+    }
+
+    #[test]
+    fn warnings_escape_keys_in_pointers() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"a/b~c": {"type": "integer"}}
+        });
+        let mut args = json!({"a/b~c": "3", "x/y": null});
+        let mut warnings = coerce_args(&mut args, &schema);
+        warnings.sort();
+        assert!(warnings[0].starts_with("/a~1b~0c: "), "{warnings:?}");
+        assert_eq!(warnings[1], "/x~1y: dropped null for optional field");
+    }
+
+    #[test]
+    fn parses_stringified_json_and_recurses_into_items() {
+        let mut args = json!({
+            "query": "x",
+            "calls": "[{\"tool\": \"type_info\", \"arguments\": \"{\\\"name\\\": 1}\"}]"
+        });
+        let warnings = coerce_args(&mut args, &schema());
+        assert_eq!(args["calls"][0]["arguments"], json!({"name": 1}));
+        assert!(warnings
+            .iter()
+            .any(|w| w.starts_with("/calls/0/arguments: converted string to object")));
+    }
+
+    #[test]
+    fn null_arguments_become_empty_object() {
+        let mut args = Value::Null;
+        coerce_args(&mut args, &schema());
+        assert_eq!(args, json!({}));
+    }
+}
@@ -1,18 +1,61 @@
-use super::SharedState;
-use crate::parser::workspace;
+use crate::toolkit::AmariToolkit;
 use async_trait::async_trait;
 use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::Write as _;
 use std::sync::Arc;
 
 pub struct DependencyGraphHandler {
-    pub state: Arc<SharedState>,
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DependencyGraphRequest {
+    #[serde(rename = "crate")]
+    pub crate_name: Option<String>,
+    pub render: Option<DiagramFormat>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagramFormat {
+    Dot,
+    Mermaid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyGraphResponse<'a> {
+    #[serde(flatten)]
+    pub graph: GraphView<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<DiagramFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagram: Option<String>,
+}
+
+/// One crate's edges, or every crate's when no crate was requested.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum GraphView<'a> {
+    Crate(CrateDependencies<'a>),
+    Full { graph: Vec<CrateDependencies<'a>> },
+}
+
+#[derive(Debug, Serialize)]
+pub struct CrateDependencies<'a> {
+    #[serde(rename = "crate")]
+    pub crate_name: &'a str,
+    pub depends_on: &'a [String],
+    pub depended_by: Vec<&'a str>,
 }
 
 #[async_trait]
 impl ToolHandler for DependencyGraphHandler {
     fn metadata(&self) -> Option<pmcp::ToolInfo> {
-        Some(super::tool_info(
+        Some(super::library_tool_info(
+            &self.toolkit,
             "dependency_graph",
             "Show inter-crate dependency relationships within the workspace",
             json!({
@@ -20,7 +63,12 @@ impl ToolHandler for DependencyGraphHandler {
                 "properties": {
                     "crate": {
                         "type": "string",
-                        "description": "Show dependencies for a specific crate, or omit for the full graph"
+                        "description": "Show dependencies for a specific crate (name or alias), or omit for the full graph"
+                    },
+                    "render": {
+                        "type": "string",
+                        "description": "Also return a diagram of the full graph; edges touching `crate` are highlighted",
+                        "enum": ["dot", "mermaid"]
                     }
                 }
             }),
@@ -28,48 +76,240 @@ impl ToolHandler for DependencyGraphHandler {
     }
 
     async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
-        let crate_filter = args.get("crate").and_then(|v| v.as_str());
+        let toolkit = self.toolkit.current();
+        let request: DependencyGraphRequest = super::parse_args(args)?;
+        // Resolve an alias to the crate name the graph is keyed by.
+        let crate_filter = match request
+            .crate_name
+            .as_deref()
+            .map(|name| toolkit.crate_info(name))
+            .transpose()
+        {
+            Ok(info) => info.map(|info| info.name.as_str()),
+            Err(e) => {
+                return Ok(json!({
+                    "error": e.to_string(),
+                    "available_crates": toolkit.crate_labels(),
+                }));
+            }
+        };
 
-        let crate_dirs: Vec<(String, &std::path::Path)> = self
-            .state
-            .index
-            .crates
-            .iter()
-            .map(|c| (c.name.clone(), c.source_dir.as_path()))
-            .collect();
+        let graph = toolkit.dependency_graph();
 
-        let graph = workspace::build_dependency_graph(&crate_dirs);
+        let diagram = request.render.map(|format| match format {
+            DiagramFormat::Dot => render_dot(&graph, crate_filter),
+            DiagramFormat::Mermaid => render_mermaid(&graph, crate_filter),
+        });
 
-        if let Some(name) = crate_filter {
-            let deps = graph.get(name).cloned().unwrap_or_default();
-            let depended_by: Vec<&str> = graph
-                .iter()
-                .filter(|(_, deps)| deps.contains(&name.to_string()))
-                .map(|(name, _)| name.as_str())
-                .collect();
-
-            Ok(json!({
-                "crate": name,
-                "depends_on": deps,
-                "depended_by": depended_by,
-            }))
-        } else {
-            let entries: Vec<Value> = graph
+        super::respond(&DependencyGraphResponse {
+            graph: graph_view(&graph, crate_filter),
+            format: request.render,
+            diagram,
+        })
+    }
+}
+
+fn graph_view<'a>(
+    graph: &'a HashMap<String, Vec<String>>,
+    crate_filter: Option<&'a str>,
+) -> GraphView<'a> {
+    let depended_by = |name: &str| -> Vec<&'a str> {
+        graph
+            .iter()
+            .filter(|(_, deps)| deps.iter().any(|d| d == name))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    };
+
+    if let Some(name) = crate_filter {
+        GraphView::Crate(CrateDependencies {
+            crate_name: name,
+            depends_on: graph.get(name).map(Vec::as_slice).unwrap_or_default(),
+            depended_by: depended_by(name),
+        })
+    } else {
+        GraphView::Full {
+            graph: graph
                 .iter()
-                .map(|(name, deps)| {
-                    let depended_by: Vec<&str> = graph
-                        .iter()
-                        .filter(|(_, d)| d.contains(name))
-                        .map(|(n, _)| n.as_str())
-                        .collect();
-                    json!({
-                        "crate": name,
-                        "depends_on": deps,
-                        "depended_by": depended_by,
-                    })
+                .map(|(name, deps)| CrateDependencies {
+                    crate_name: name,
+                    depends_on: deps,
+                    depended_by: depended_by(name),
                 })
-                .collect();
-            Ok(json!({ "graph": entries }))
+                .collect(),
+        }
+    }
+}
+
+/// Edges of the graph as sorted `(from, to)` pairs for stable diagram output.
+fn sorted_edges(graph: &HashMap<String, Vec<String>>) -> Vec<(&str, &str)> {
+    let mut edges: Vec<(&str, &str)> = graph
+        .iter()
+        .flat_map(|(from, deps)| deps.iter().map(move |to| (from.as_str(), to.as_str())))
+        .collect();
+    edges.sort_unstable();
+    edges
+}
+
+fn sorted_nodes(graph: &HashMap<String, Vec<String>>) -> Vec<&str> {
+    let mut nodes: Vec<&str> = graph.keys().map(String::as_str).collect();
+    nodes.sort_unstable();
+    nodes
+}
+
+/// Render the graph as Graphviz DOT, drawing edges touching `highlight` bold red.
+fn render_dot(graph: &HashMap<String, Vec<String>>, highlight: Option<&str>) -> String {
+    let mut out = String::from("digraph dependencies {\n    rankdir=LR;\n");
+    for node in sorted_nodes(graph) {
+        if Some(node) == highlight {
+            let _ = writeln!(out, "    \"{node}\" [style=filled, fillcolor=lightyellow];");
+        } else {
+            let _ = writeln!(out, "    \"{node}\";");
+        }
+    }
+    for (from, to) in sorted_edges(graph) {
+        if highlight.is_some_and(|h| h == from || h == to) {
+            let _ = writeln!(out, "    \"{from}\" -> \"{to}\" [color=red, penwidth=2];");
+        } else {
+            let _ = writeln!(out, "    \"{from}\" -> \"{to}\";");
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Render the graph as a Mermaid flowchart, styling edges touching `highlight`.
+fn render_mermaid(graph: &HashMap<String, Vec<String>>, highlight: Option<&str>) -> String {
+    let mut out = String::from("graph LR\n");
+    for node in sorted_nodes(graph) {
+        let _ = writeln!(out, "    {}[\"{node}\"]", mermaid_id(node));
+    }
+    let mut highlighted = Vec::new();
+    for (position, (from, to)) in sorted_edges(graph).into_iter().enumerate() {
+        let _ = writeln!(out, "    {} --> {}", mermaid_id(from), mermaid_id(to));
+        if highlight.is_some_and(|h| h == from || h == to) {
+            highlighted.push(position.to_string());
         }
     }
+    if let Some(node) = highlight.filter(|node| graph.contains_key(*node)) {
+        let _ = writeln!(out, "    style {} fill:#ffd", mermaid_id(node));
+    }
+    if !highlighted.is_empty() {
+        let _ = writeln!(
+            out,
+            "    linkStyle {} stroke:red,stroke-width:2px",
+            highlighted.join(",")
+        );
+    }
+    out
+}
+
+/// Mermaid node ids may not contain `-`.
+fn mermaid_id(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_graph() -> HashMap<String, Vec<String>> {
+        HashMap::from([
+            ("lib-core".to_string(), Vec::new()),
+            ("lib-gpu".to_string(), vec!["lib-core".to_string()]),
+            ("lib-net".to_string(), vec!["lib-core".to_string()]),
+        ])
+    }
+
+    #[test]
+    fn response_flattens_selected_crate() {
+        let graph = sample_graph();
+        let response = DependencyGraphResponse {
+            graph: graph_view(&graph, Some("lib-gpu")),
+            format: None,
+            diagram: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({"crate": "lib-gpu", "depends_on": ["lib-core"], "depended_by": []})
+        );
+    }
+
+    #[test]
+    fn request_parses_render_format() {
+        let request: DependencyGraphRequest =
+            serde_json::from_value(json!({"crate": "lib-gpu", "render": "dot"})).unwrap();
+        assert_eq!(request.crate_name.as_deref(), Some("lib-gpu"));
+        assert!(matches!(request.render, Some(DiagramFormat::Dot)));
+        assert!(
+            serde_json::from_value::<DependencyGraphRequest>(json!({"render": "svg"})).is_err()
+        );
+    }
+
+    #[test]
+    fn dot_output_lists_nodes_and_edges() {
+        let dot = render_dot(&sample_graph(), None);
+        assert!(dot.starts_with("digraph dependencies {"));
+        assert!(dot.contains("\"lib-gpu\" -> \"lib-core\";"));
+        assert!(dot.contains("\"lib-net\" -> \"lib-core\";"));
+        assert!(!dot.contains("color=red"));
+    }
+
+    #[test]
+    fn dot_highlights_edges_of_selected_crate() {
+        let dot = render_dot(&sample_graph(), Some("lib-gpu"));
+        assert!(dot.contains("\"lib-gpu\" -> \"lib-core\" [color=red, penwidth=2];"));
+        assert!(dot.contains("\"lib-net\" -> \"lib-core\";"));
+    }
+
+    #[test]
+    fn mermaid_uses_safe_ids_and_link_styles() {
+        let mermaid = render_mermaid(&sample_graph(), Some("lib-net"));
+        assert!(mermaid.starts_with("graph LR"));
+        assert!(mermaid.contains("lib_core[\"lib-core\"]"));
+        assert!(mermaid.contains("lib_net --> lib_core"));
+        // Edges sort as (lib-gpu, lib-core), (lib-net, lib-core)
+        assert!(mermaid.contains("linkStyle 1 stroke:red"));
+        assert!(mermaid.contains("style lib_net fill:#ffd"));
+    }
+
+    #[test]
+    fn highlight_of_a_missing_crate_adds_no_node() {
+        let mermaid = render_mermaid(&sample_graph(), Some("core"));
+        assert!(!mermaid.contains("style"), "{mermaid}");
+        assert_eq!(mermaid, render_mermaid(&sample_graph(), None));
+        assert_eq!(
+            render_dot(&sample_graph(), Some("core")),
+            render_dot(&sample_graph(), None)
+        );
+    }
+
+    #[tokio::test]
+    async fn handler_resolves_aliases_and_rejects_unknown_crates() {
+        let manifest =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/minilib.toml");
+        let handler = DependencyGraphHandler {
+            toolkit: Arc::new(AmariToolkit::load(&manifest).expect("fixture should index")),
+        };
+        let extra = crate::tools::registry::local_extra();
+
+        let response = handler
+            .handle(json!({"crate": "core", "render": "mermaid"}), extra.clone())
+            .await
+            .unwrap();
+        assert_eq!(response["crate"], "minilib-core");
+        let diagram = response["diagram"].as_str().unwrap();
+        assert!(
+            diagram.contains("style minilib_core fill:#ffd"),
+            "{diagram}"
+        );
+        assert!(!diagram.contains("    style core "), "{diagram}");
+
+        let response = handler
+            .handle(json!({"crate": "nope", "render": "dot"}), extra)
+            .await
+            .unwrap();
+        assert!(response["error"].as_str().unwrap().contains("nope"));
+        assert!(response.get("diagram").is_none());
+    }
 }
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// Tracks tool calls in progress so the server can stop admitting new calls
+/// at shutdown and wait for the running ones to finish.
+#[derive(Default)]
+pub struct InFlight {
+    draining: AtomicBool,
+    next_id: AtomicU64,
+    calls: Mutex<HashMap<u64, CancellationToken>>,
+    idle: Notify,
+}
+
+/// Registration of one running call; dropping it marks the call finished.
+pub struct InFlightGuard {
+    tracker: Arc<InFlight>,
+    id: u64,
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a call, unless the server is draining.
+    pub fn begin(self: &Arc<Self>, token: CancellationToken) -> Result<InFlightGuard, String> {
+        if self.is_draining() {
+            return Err("Server is shutting down; not accepting new tool calls".to_string());
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().insert(id, token);
+        Ok(InFlightGuard {
+            tracker: self.clone(),
+            id,
+        })
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Number of calls currently running.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Stop admitting calls and wait up to `timeout` for running ones to
+    /// finish. Calls still running afterwards are cancelled through their
+    /// tokens; returns how many were cancelled.
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        self.draining.store(true, Ordering::SeqCst);
+
+        let finished = tokio::time::timeout(timeout, async {
+            loop {
+                let notified = self.idle.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.is_empty() {
+                    return;
+                }
+                notified.await;
+            }
+        })
+        .await;
+
+        if finished.is_ok() {
+            return 0;
+        }
+        let calls = self.lock();
+        for token in calls.values() {
+            token.cancel();
+        }
+        calls.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, CancellationToken>> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut calls = self.tracker.lock();
+        calls.remove(&self.id);
+        if calls.is_empty() {
+            self.tracker.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guards_track_running_calls() {
+        let tracker = Arc::new(InFlight::new());
+        let first = tracker.begin(CancellationToken::new()).unwrap();
+        let second = tracker.begin(CancellationToken::new()).unwrap();
+        assert_eq!(tracker.len(), 2);
+        drop(first);
+        assert_eq!(tracker.len(), 1);
+        drop(second);
+        assert!(tracker.is_empty());
+    }
+
+    #[tokio::test]
+    async fn drain_rejects_new_calls_and_returns_when_idle() {
+        let tracker = Arc::new(InFlight::new());
+        assert_eq!(tracker.drain(Duration::from_millis(10)).await, 0);
+        assert!(tracker.begin(CancellationToken::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn drain_waits_for_running_calls() {
+        let tracker = Arc::new(InFlight::new());
+        let guard = tracker.begin(CancellationToken::new()).unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+        assert_eq!(tracker.drain(Duration::from_secs(5)).await, 0);
+    }
+
+    #[tokio::test]
+    async fn drain_cancels_calls_past_the_timeout() {
+        let tracker = Arc::new(InFlight::new());
+        let token = CancellationToken::new();
+        let _guard = tracker.begin(token.clone()).unwrap();
+        assert_eq!(tracker.drain(Duration::from_millis(10)).await, 1);
+        assert!(token.is_cancelled());
+    }
+}
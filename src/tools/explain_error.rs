@@ -0,0 +1,134 @@
+use crate::parser::index::ApiItem;
+use crate::toolkit::{kind_label, AmariToolkit, NameReport};
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub struct ExplainErrorHandler {
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExplainErrorRequest {
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExplainErrorResponse<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<&'static str>,
+    pub causes: &'static [&'static str],
+    pub fixes: &'static [&'static str],
+    pub names: Vec<NameView<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NameView<'a> {
+    pub name: &'a str,
+    pub items: Vec<ItemView<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub did_you_mean: Vec<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ItemView<'a> {
+    pub full_path: &'a str,
+    pub kind: &'static str,
+    pub signature: &'a str,
+    /// Cargo feature of the library enabling the item's crate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requires_feature: Option<&'a str>,
+    /// `#[cfg(feature = ...)]` on the item inside its crate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cfg_feature: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<&'a str>,
+}
+
+#[async_trait]
+impl ToolHandler for ExplainErrorHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::library_tool_info(
+            &self.toolkit,
+            "explain_error",
+            "Explain a rustc error involving the library: likely causes and fixes, plus the feature gates, paths, and similar names the index has for each item it mentions",
+            json!({
+                "type": "object",
+                "properties": {
+                    "message": {
+                        "type": "string",
+                        "description": "The compiler error, e.g. \"error[E0432]: unresolved import `amari::gpu`\""
+                    }
+                },
+                "required": ["message"]
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let toolkit = self.toolkit.current();
+        let request: ExplainErrorRequest = super::parse_args(args)?;
+        let limits = &toolkit.manifest().server.limits;
+        // Compiler messages run long, so allow several query lengths.
+        let max_chars = limits.max_query_length.saturating_mul(16);
+        let length = request.message.chars().count();
+        if length > max_chars {
+            return Err(McpError::invalid_params(format!(
+                "message is {length} characters, maximum is {max_chars}"
+            )));
+        }
+
+        let explanation = match toolkit.explain_error(&request.message) {
+            Ok(explanation) => explanation,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
+        };
+
+        super::respond(&ExplainErrorResponse {
+            code: explanation.code.as_deref(),
+            title: explanation.known.map(|k| k.title),
+            causes: explanation.known.map(|k| k.causes).unwrap_or_default(),
+            fixes: explanation.known.map(|k| k.fixes).unwrap_or_default(),
+            names: explanation
+                .names
+                .iter()
+                .map(|report| self.name_view(report))
+                .collect(),
+        })
+    }
+}
+
+impl ExplainErrorHandler {
+    fn name_view<'a>(&'a self, report: &'a NameReport<'a>) -> NameView<'a> {
+        NameView {
+            name: &report.name,
+            items: report
+                .items
+                .iter()
+                .map(|&item| self.item_view(item))
+                .collect(),
+            did_you_mean: report
+                .suggestions
+                .iter()
+                .map(|item| item.full_path.as_str())
+                .collect(),
+        }
+    }
+
+    fn item_view<'a>(&'a self, item: &'a ApiItem) -> ItemView<'a> {
+        ItemView {
+            full_path: &item.full_path,
+            kind: kind_label(&item.kind),
+            signature: &item.signature,
+            requires_feature: self
+                .toolkit
+                .crate_of(item)
+                .and_then(|c| c.feature_gate.as_deref()),
+            cfg_feature: item.feature_gate.as_deref(),
+            deprecated: item.deprecated.as_deref(),
+        }
+    }
+}
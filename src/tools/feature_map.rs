@@ -1,17 +1,39 @@
-use super::SharedState;
+use crate::toolkit::AmariToolkit;
 use async_trait::async_trait;
 use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
 pub struct FeatureMapHandler {
-    pub state: Arc<SharedState>,
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeatureMapRequest {
+    pub feature: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeatureMapResponse<'a> {
+    pub library: &'a str,
+    pub default_crates: Vec<&'a str>,
+    pub optional_features: Vec<OptionalFeature<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptionalFeature<'a> {
+    pub feature: &'a str,
+    pub crate_dir: &'a str,
+    pub alias: Option<&'a str>,
+    pub public_item_count: usize,
 }
 
 #[async_trait]
 impl ToolHandler for FeatureMapHandler {
     fn metadata(&self) -> Option<pmcp::ToolInfo> {
-        Some(super::tool_info(
+        Some(super::library_tool_info(
+            &self.toolkit,
             "feature_map",
             "Show which Cargo features enable which crates and types",
             json!({
@@ -27,40 +49,23 @@ impl ToolHandler for FeatureMapHandler {
     }
 
     async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
-        let feature_filter = args.get("feature").and_then(|v| v.as_str());
+        let toolkit = self.toolkit.current();
+        let request: FeatureMapRequest = super::parse_args(args)?;
+        let map = toolkit.features(request.feature.as_deref());
 
-        let default_crates: Vec<&str> = self
-            .state
-            .index
-            .crates
-            .iter()
-            .filter(|c| c.feature_gate.is_none())
-            .map(|c| c.name.as_str())
-            .collect();
-
-        let features: Vec<Value> = self
-            .state
-            .manifest
-            .crates
-            .optional
-            .iter()
-            .filter(|(feature, _)| feature_filter.is_none_or(|f| f == feature.as_str()))
-            .map(|(feature, dir_name)| {
-                let alias = self.state.manifest.alias_for(dir_name);
-                let item_count = self.state.index.feature_items(feature).len();
-                json!({
-                    "feature": feature,
-                    "crate_dir": dir_name,
-                    "alias": alias,
-                    "public_item_count": item_count,
+        super::respond(&FeatureMapResponse {
+            library: map.library,
+            optional_features: map
+                .optional
+                .iter()
+                .map(|info| OptionalFeature {
+                    feature: info.feature,
+                    crate_dir: info.crate_dir,
+                    alias: info.alias,
+                    public_item_count: info.public_item_count,
                 })
-            })
-            .collect();
-
-        Ok(json!({
-            "library": self.state.manifest.library.name,
-            "default_crates": default_crates,
-            "optional_features": features,
-        }))
+                .collect(),
+            default_crates: map.default_crates,
+        })
     }
 }
@@ -0,0 +1,106 @@
+//! Tools defined by a single async function.
+//!
+//! [`FnToolHandler`] pairs a tool's metadata with a closure taking its typed
+//! request, so a simple tool needs no handler struct or `ToolHandler` impl:
+//!
+//! ```ignore
+//! tools.register_fn("echo", "Return the message", schema, |req: EchoRequest, _extra| async move {
+//!     Ok(json!({ "message": req.message }))
+//! });
+//! ```
+
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler, ToolInfo};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::future::Future;
+use std::marker::PhantomData;
+
+/// A [`ToolHandler`] that deserializes arguments into `Req` and calls `F`.
+pub struct FnToolHandler<Req, F> {
+    info: ToolInfo,
+    handler: F,
+    _request: PhantomData<fn() -> Req>,
+}
+
+impl<Req, F> FnToolHandler<Req, F> {
+    /// Build the handler; metadata goes through [`super::tool_info`] like
+    /// every other tool, so it gains the catalog hints and `verbosity`.
+    pub fn new(name: &str, description: &str, input_schema: Value, handler: F) -> Self {
+        Self {
+            info: super::tool_info(name, description, input_schema),
+            handler,
+            _request: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<Req, F, Fut> ToolHandler for FnToolHandler<Req, F>
+where
+    Req: DeserializeOwned + Send + 'static,
+    F: Fn(Req, RequestHandlerExtra) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Value, McpError>> + Send,
+{
+    fn metadata(&self) -> Option<ToolInfo> {
+        Some(self.info.clone())
+    }
+
+    async fn handle(&self, args: Value, extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let request = super::parse_args(args)?;
+        (self.handler)(request, extra).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::registry::local_extra;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct GreetRequest {
+        name: String,
+    }
+
+    fn greet() -> impl ToolHandler {
+        FnToolHandler::new(
+            "greet",
+            "Say hello",
+            json!({
+                "type": "object",
+                "properties": {"name": {"type": "string"}},
+                "required": ["name"]
+            }),
+            |request: GreetRequest, _extra: RequestHandlerExtra| async move {
+                Ok(json!({ "greeting": format!("hello {}", request.name) }))
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn passes_typed_request_to_function() {
+        let response = greet()
+            .handle(json!({"name": "rotor"}), local_extra())
+            .await
+            .unwrap();
+        assert_eq!(response, json!({"greeting": "hello rotor"}));
+    }
+
+    #[tokio::test]
+    async fn rejects_arguments_of_the_wrong_shape() {
+        let err = greet()
+            .handle(json!({"name": 3}), local_extra())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid type"));
+    }
+
+    #[test]
+    fn metadata_uses_the_given_name() {
+        let info = greet().metadata().unwrap();
+        assert_eq!(info.name, "greet");
+        assert!(info.input_schema["properties"].get("verbosity").is_some());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::config::ResourceLimits;
+use pmcp::{Error as McpError, ErrorCode};
+use serde_json::Value;
+
+/// Resolve the optional `limit` argument, falling back to `default` and
+/// rejecting values above the configured maximum.
+pub fn result_limit(
+    requested: Option<u64>,
+    default: usize,
+    limits: &ResourceLimits,
+) -> Result<usize, McpError> {
+    let Some(requested) = requested else {
+        return Ok(default.min(limits.max_results));
+    };
+    let requested = usize::try_from(requested).unwrap_or(usize::MAX);
+    if requested > limits.max_results {
+        return Err(McpError::invalid_params(format!(
+            "limit {requested} exceeds the maximum of {}",
+            limits.max_results
+        )));
+    }
+    Ok(requested)
+}
+
+/// Reject query and name strings longer than the configured maximum.
+pub fn check_query(field: &str, value: &str, limits: &ResourceLimits) -> Result<(), McpError> {
+    let length = value.chars().count();
+    if length > limits.max_query_length {
+        return Err(McpError::invalid_params(format!(
+            "{field} is {length} characters, maximum is {}",
+            limits.max_query_length
+        )));
+    }
+    Ok(())
+}
+
+/// Size of `value` serialized as compact JSON, without building the string.
+pub fn json_size(value: &Value) -> usize {
+    struct ByteCounter(usize);
+
+    impl std::io::Write for ByteCounter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = ByteCounter(0);
+    // Writing to the counter can't fail, and a `Value` always serializes.
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
+/// Reject responses larger than `max_bytes` once serialized.
+pub fn check_response_size(tool: &str, response: &Value, max_bytes: usize) -> Result<(), McpError> {
+    let size = json_size(response);
+    if size > max_bytes {
+        return Err(McpError::protocol(
+            ErrorCode::INVALID_REQUEST,
+            format!(
+                "{tool} response is {size} bytes, maximum is {max_bytes}; narrow the query or lower `limit`"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_size_matches_serialized_length() {
+        let value = json!({"name": "Rotor", "items": [1, 2, 3], "doc": "ünïcode"});
+        assert_eq!(json_size(&value), serde_json::to_vec(&value).unwrap().len());
+    }
+
+    #[test]
+    fn check_response_size_rejects_oversized_responses() {
+        let response = json!({"results": ["a", "b"]});
+        let size = json_size(&response);
+        assert!(check_response_size("api_search", &response, size).is_ok());
+        let err = check_response_size("api_search", &response, size - 1).unwrap_err();
+        assert!(err.to_string().contains("api_search"));
+    }
+
+    #[test]
+    fn result_limit_uses_default_when_absent() {
+        let limits = ResourceLimits::default();
+        assert_eq!(result_limit(None, 20, &limits).unwrap(), 20);
+    }
+
+    #[test]
+    fn result_limit_default_never_exceeds_maximum() {
+        let limits = ResourceLimits {
+            max_results: 10,
+            ..ResourceLimits::default()
+        };
+        assert_eq!(result_limit(None, 50, &limits).unwrap(), 10);
+    }
+
+    #[test]
+    fn result_limit_rejects_values_over_maximum() {
+        let limits = ResourceLimits::default();
+        assert_eq!(result_limit(Some(200), 20, &limits).unwrap(), 200);
+        assert!(result_limit(Some(201), 20, &limits).is_err());
+    }
+
+    #[test]
+    fn check_query_counts_characters() {
+        let limits = ResourceLimits {
+            max_query_length: 3,
+            ..ResourceLimits::default()
+        };
+        assert!(check_query("query", "äöü", &limits).is_ok());
+        assert!(check_query("query", "abcd", &limits).is_err());
+    }
+}
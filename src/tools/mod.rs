@@ -1,35 +1,504 @@
+pub mod api_diff;
 pub mod api_search;
+pub mod batch_call;
 pub mod browse_docs;
+pub mod capability_matrix;
+pub mod catalog;
+pub mod changelog;
+pub mod coerce;
 pub mod dependency_graph;
+pub mod drain;
+pub mod explain_error;
 pub mod feature_map;
+pub mod fn_tool;
+pub mod limits;
 pub mod module_overview;
+pub mod plugin;
+pub mod profile;
+pub mod registry;
+pub mod resolve_features;
+pub mod search_docs;
+pub mod server_info;
+pub mod session;
+pub mod throttle;
 pub mod type_info;
+pub mod upgrade_advice;
+pub mod usage;
 pub mod usage_examples;
+pub mod validate;
+pub mod verbosity;
 
 use crate::config::LibraryManifest;
+use crate::embeddings::{BagOfWords, EmbeddingProvider};
 use crate::parser::index::{ApiIndex, Validated};
+use crate::toolkit::AmariToolkit;
+use crate::watch::WatchStatus;
+use catalog::ExampleValues;
+use plugin::PluginRegistry;
 use pmcp::ToolInfo;
+use registry::ToolRegistry;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use throttle::Throttle;
 
 /// Construct a ToolInfo. pmcp marks ToolInfo as #[non_exhaustive], so we
 /// can't use struct literal syntax. This helper builds one from Default.
+/// The description is extended with the tool's tags, cost, and examples
+/// from the [`catalog`], and the schema with the shared `verbosity` argument.
+/// Examples that name library items are left out; see [`library_tool_info`].
 pub fn tool_info(name: &str, description: &str, input_schema: Value) -> ToolInfo {
+    tool_info_with(name, description, input_schema, &ExampleValues::default())
+}
+
+/// [`tool_info`] for a tool serving `toolkit`'s library, with the catalog
+/// examples filled in from its manifest and index.
+pub fn library_tool_info(
+    toolkit: &AmariToolkit,
+    name: &str,
+    description: &str,
+    input_schema: Value,
+) -> ToolInfo {
+    let values = ExampleValues::from_toolkit(toolkit);
+    tool_info_with(name, description, input_schema, &values)
+}
+
+fn tool_info_with(
+    name: &str,
+    description: &str,
+    mut input_schema: Value,
+    values: &ExampleValues,
+) -> ToolInfo {
+    if let Some(properties) = input_schema
+        .get_mut("properties")
+        .and_then(|p| p.as_object_mut())
+    {
+        properties.insert(
+            verbosity::ARGUMENT.to_string(),
+            verbosity::schema_property(),
+        );
+    }
+
     let mut info = ToolInfo::default();
     info.name = name.to_string();
-    info.description = Some(description.to_string());
+    info.description = Some(catalog::describe(name, description, values));
     info.input_schema = input_schema;
     info
 }
 
+/// Schema of the `workspace` argument accepted by the browse and search
+/// tools, selecting a `[workspaces]` library instead of the primary one.
+pub fn workspace_property() -> Value {
+    serde_json::json!({
+        "type": "string",
+        "description": "Answer from this library in the manifest's [workspaces] table instead of the primary one"
+    })
+}
+
+/// Deserialize a tool's arguments into its typed request.
+pub fn parse_args<T: DeserializeOwned>(args: Value) -> Result<T, pmcp::Error> {
+    serde_json::from_value(args).map_err(|e| pmcp::Error::invalid_params(e.to_string()))
+}
+
+/// Serialize a typed response into the JSON returned to the client.
+pub fn respond<T: Serialize>(response: &T) -> Result<Value, pmcp::Error> {
+    serde_json::to_value(response).map_err(|e| pmcp::Error::internal(e.to_string()))
+}
+
 /// Shared state passed to all tool handlers.
 pub struct SharedState {
     pub index: ApiIndex<Validated>,
     pub manifest: LibraryManifest,
+    pub started_at: Instant,
+    /// Source files changed since `index` was built.
+    pub watch: Arc<WatchStatus>,
+    /// Embeds docs and queries for semantic `search_docs`.
+    pub embeddings: Arc<dyn EmbeddingProvider>,
+    /// The state over the latest re-index, once the watch has rebuilt one.
+    reindexed: Mutex<Option<Arc<SharedState>>>,
 }
 
 impl SharedState {
     pub fn new(index: ApiIndex<Validated>, manifest: LibraryManifest) -> Arc<Self> {
-        Arc::new(Self { index, manifest })
+        Self::with_embeddings(index, manifest, Arc::new(BagOfWords))
+    }
+
+    /// [`SharedState::new`] with a custom embedding provider in place of
+    /// [`BagOfWords`].
+    pub fn with_embeddings(
+        index: ApiIndex<Validated>,
+        manifest: LibraryManifest,
+        embeddings: Arc<dyn EmbeddingProvider>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            index,
+            manifest,
+            started_at: Instant::now(),
+            watch: Arc::new(WatchStatus::new()),
+            embeddings,
+            reindexed: Mutex::new(None),
+        })
+    }
+
+    /// The state tools should answer from: the latest [`SharedState::reindex`],
+    /// or this one if the source hasn't been re-indexed.
+    pub fn current(self: &Arc<Self>) -> Arc<Self> {
+        self.lock_reindexed()
+            .clone()
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// Serve `index`, rebuilt from the same manifest, in place of the
+    /// current one. Uptime, watch status and embeddings carry over.
+    pub fn reindex(&self, index: ApiIndex<Validated>) {
+        *self.lock_reindexed() = Some(Arc::new(Self {
+            index,
+            manifest: self.manifest.clone(),
+            started_at: self.started_at,
+            watch: self.watch.clone(),
+            embeddings: self.embeddings.clone(),
+            reindexed: Mutex::new(None),
+        }));
+    }
+
+    fn lock_reindexed(&self) -> std::sync::MutexGuard<'_, Option<Arc<SharedState>>> {
+        self.reindexed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Assemble every tool the server exposes: the reference tools plus
+/// `batch_call`, the session tools, `usage_stats`, `capability_matrix` and
+/// `server_info`,
+/// filtered by the configured profile and wrapped in the configured throttle.
+pub fn build_tools(state: &Arc<SharedState>) -> ToolRegistry {
+    build_tools_with_plugins(state, &PluginRegistry::new())
+}
+
+/// [`build_tools`], plus the tools of each plugin named in `[server.plugins]`.
+/// Plugin tools bypass the profile but honor `disable_tools`, and can be
+/// called from `batch_call` and `replay_script` like reference tools.
+pub fn build_tools_with_plugins(
+    state: &Arc<SharedState>,
+    plugins: &PluginRegistry,
+) -> ToolRegistry {
+    let config = &state.manifest.server;
+
+    let mut reference = reference_tools(state);
+    let mut known = reference.names();
+    known.extend([
+        "batch_call",
+        "record_session",
+        "replay_script",
+        "usage_stats",
+        "capability_matrix",
+        "server_info",
+    ]);
+    let plugin::InstalledPlugins {
+        tools: mut plugged,
+        implementations: plugin_statuses,
+        problems,
+    } = plugins.install(state, &known);
+    for problem in problems {
+        tracing::warn!("{problem}");
+    }
+    let known: Vec<&str> = known.into_iter().chain(plugged.names()).collect();
+    for name in profile::unknown_tool_names(config, &known) {
+        tracing::warn!("Unknown tool '{name}' in enable_tools/disable_tools");
+    }
+    reference.retain(|name| profile::tool_enabled(config, name));
+    plugged.retain(|name| !config.disable_tools.iter().any(|t| t == name));
+    reference.extend(plugged);
+
+    // Calls inside a batch or replay are admitted as part of the enclosing
+    // call, so the registries handed to them are unthrottled and unrecorded.
+    let reference = Arc::new(reference);
+    let mut composite = (*reference).clone();
+    if profile::tool_enabled(config, "batch_call") {
+        composite.register(
+            "batch_call",
+            batch_call::BatchCallHandler {
+                tools: reference,
+                max_calls: config.limits.max_batch_calls,
+            },
+        );
+    }
+    let composite = Arc::new(composite);
+
+    let recorder = Arc::new(session::CallRecorder::new(session::DEFAULT_CAPACITY));
+    let mut tools = (*composite)
+        .clone()
+        .with_throttle(Arc::new(Throttle::from_config(config)))
+        .with_recorder(recorder.clone())
+        .with_stats(Arc::new(usage::UsageStats::new()))
+        .with_in_flight(Arc::new(drain::InFlight::new()))
+        .with_response_limit(config.limits.max_response_bytes);
+    if profile::tool_enabled(config, "record_session") {
+        tools.register(
+            "record_session",
+            session::RecordSessionHandler {
+                state: state.clone(),
+                recorder,
+            },
+        );
+    }
+    if profile::tool_enabled(config, "replay_script") {
+        tools.register(
+            "replay_script",
+            session::ReplayScriptHandler {
+                state: state.clone(),
+                tools: composite,
+            },
+        );
+    }
+    if profile::tool_enabled(config, "usage_stats") {
+        if let Some(stats) = tools.stats().cloned() {
+            usage::register_usage_stats(&mut tools, stats);
+        }
+    }
+    // Both report the full tool list, each including the other and itself.
+    let mut names: Vec<String> = tools.names().into_iter().map(String::from).collect();
+    for tool in ["capability_matrix", "server_info"] {
+        if profile::tool_enabled(config, tool) {
+            names.push(tool.to_string());
+        }
+    }
+    if profile::tool_enabled(config, "capability_matrix") {
+        tools.register(
+            "capability_matrix",
+            capability_matrix::CapabilityMatrixHandler {
+                tools: names.clone(),
+                plugins: plugin_statuses,
+            },
+        );
+    }
+    if profile::tool_enabled(config, "server_info") {
+        tools.register(
+            "server_info",
+            server_info::ServerInfoHandler {
+                state: state.clone(),
+                tools: names,
+            },
+        );
+    }
+    tools
+}
+
+/// Build the registry of reference tools, all wrapping one [`AmariToolkit`]
+/// over the shared index.
+pub fn reference_tools(state: &Arc<SharedState>) -> ToolRegistry {
+    let toolkit = Arc::new(AmariToolkit::new(state.clone()));
+    let mut tools = ToolRegistry::new();
+    tools.register(
+        "api_search",
+        api_search::ApiSearchHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "type_info",
+        type_info::TypeInfoHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "module_overview",
+        module_overview::ModuleOverviewHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "feature_map",
+        feature_map::FeatureMapHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "dependency_graph",
+        dependency_graph::DependencyGraphHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "browse_docs",
+        browse_docs::BrowseDocsHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "usage_examples",
+        usage_examples::UsageExamplesHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "resolve_features",
+        resolve_features::ResolveFeaturesHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "explain_error",
+        explain_error::ExplainErrorHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "search_docs",
+        search_docs::SearchDocsHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "changelog",
+        changelog::ChangelogHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "api_diff",
+        api_diff::ApiDiffHandler {
+            toolkit: toolkit.clone(),
+        },
+    );
+    tools.register(
+        "upgrade_advice",
+        upgrade_advice::UpgradeAdviceHandler { toolkit },
+    );
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::value::Error as DeError;
+    use serde::de::{Deserialize, Deserializer, Error as _, Visitor};
+    use serde_json::{json, Map};
+    use std::path::Path;
+
+    /// Deserializer that fails on purpose, reporting the field names serde
+    /// derived for the struct it was asked to build.
+    struct FieldNames;
+
+    impl<'de> Deserializer<'de> for FieldNames {
+        type Error = DeError;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, DeError> {
+            Err(DeError::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, DeError> {
+            Err(DeError::custom(fields.join(",")))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    fn request_fields<T: for<'de> Deserialize<'de>>() -> Vec<String> {
+        match T::deserialize(FieldNames) {
+            Ok(_) => panic!("FieldNames never builds a value"),
+            Err(e) => e.to_string().split(',').map(String::from).collect(),
+        }
+    }
+
+    /// A value of the type a schema property declares.
+    fn sample(property: &Value) -> Value {
+        if let Some(first) = property["enum"].as_array().and_then(|e| e.first()) {
+            return first.clone();
+        }
+        match property["type"].as_str() {
+            Some("string") => json!("x"),
+            Some("integer") | Some("number") => json!(1),
+            Some("boolean") => json!(true),
+            Some("array") => json!([]),
+            Some("object") => json!({}),
+            _ => Value::Null,
+        }
+    }
+
+    /// Check that `tool`'s published schema lists exactly the fields of its
+    /// request struct `T`, requiring those `T` cannot do without.
+    fn assert_schema_matches<T: for<'de> Deserialize<'de>>(tools: &ToolRegistry, tool: &str) {
+        let info = tools.get(tool).unwrap().metadata().unwrap();
+        let mut properties = info.input_schema["properties"].as_object().unwrap().clone();
+        properties.remove(verbosity::ARGUMENT);
+
+        let mut published: Vec<String> = properties.keys().cloned().collect();
+        let mut fields = request_fields::<T>();
+        published.sort();
+        fields.sort();
+        assert_eq!(published, fields, "{tool} properties");
+
+        let full: Map<String, Value> = properties
+            .iter()
+            .map(|(name, property)| (name.clone(), sample(property)))
+            .collect();
+        if let Err(e) = serde_json::from_value::<T>(Value::Object(full.clone())) {
+            panic!("{tool} rejects its own schema's types: {e}");
+        }
+
+        let mut required: Vec<String> = info.input_schema["required"]
+            .as_array()
+            .map(|r| {
+                r.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut needed: Vec<String> = fields
+            .into_iter()
+            .filter(|field| {
+                let mut args = full.clone();
+                args.remove(field);
+                serde_json::from_value::<T>(Value::Object(args)).is_err()
+            })
+            .collect();
+        required.sort();
+        needed.sort();
+        assert_eq!(required, needed, "{tool} required");
+    }
+
+    #[test]
+    fn schemas_match_request_structs() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/minilib.toml");
+        let toolkit = AmariToolkit::load(&manifest).expect("fixture should index");
+        let tools = build_tools(toolkit.state());
+
+        assert_schema_matches::<api_search::ApiSearchRequest>(&tools, "api_search");
+        assert_schema_matches::<type_info::TypeInfoRequest>(&tools, "type_info");
+        assert_schema_matches::<module_overview::ModuleOverviewRequest>(&tools, "module_overview");
+        assert_schema_matches::<feature_map::FeatureMapRequest>(&tools, "feature_map");
+        assert_schema_matches::<dependency_graph::DependencyGraphRequest>(
+            &tools,
+            "dependency_graph",
+        );
+        assert_schema_matches::<browse_docs::BrowseDocsRequest>(&tools, "browse_docs");
+        assert_schema_matches::<usage_examples::UsageExamplesRequest>(&tools, "usage_examples");
+        assert_schema_matches::<resolve_features::ResolveFeaturesRequest>(
+            &tools,
+            "resolve_features",
+        );
+        assert_schema_matches::<explain_error::ExplainErrorRequest>(&tools, "explain_error");
+        assert_schema_matches::<search_docs::SearchDocsRequest>(&tools, "search_docs");
+        assert_schema_matches::<changelog::ChangelogRequest>(&tools, "changelog");
+        assert_schema_matches::<api_diff::ApiDiffRequest>(&tools, "api_diff");
+        assert_schema_matches::<upgrade_advice::UpgradeAdviceRequest>(&tools, "upgrade_advice");
+        assert_schema_matches::<batch_call::BatchCallRequest>(&tools, "batch_call");
+        assert_schema_matches::<session::ReplayScriptRequest>(&tools, "replay_script");
+        assert_schema_matches::<usage::UsageStatsRequest>(&tools, "usage_stats");
+        assert_schema_matches::<capability_matrix::CapabilityMatrixRequest>(
+            &tools,
+            "capability_matrix",
+        );
     }
 }
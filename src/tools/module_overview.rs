@@ -1,19 +1,55 @@
-use super::SharedState;
 use crate::parser::display;
-use crate::parser::index::ModuleInfo;
+use crate::toolkit::{kind_label, AmariToolkit, LookupError};
 use async_trait::async_trait;
 use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
 pub struct ModuleOverviewHandler {
-    pub state: Arc<SharedState>,
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModuleOverviewRequest {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub module: Option<String>,
+    pub limit: Option<u64>,
+    pub workspace: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModuleOverviewResponse<'a> {
+    #[serde(rename = "crate")]
+    pub crate_name: &'a str,
+    pub module: &'a str,
+    pub module_docs: &'a str,
+    pub feature_gate: Option<&'a str>,
+    pub items: Vec<ModuleItem<'a>>,
+    pub submodules: Vec<SubmoduleSummary<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModuleItem<'a> {
+    pub kind: &'static str,
+    pub name: &'a str,
+    pub signature: &'a str,
+    pub doc_summary: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmoduleSummary<'a> {
+    pub name: &'a str,
+    pub item_count: usize,
+    pub feature_gate: Option<&'a str>,
 }
 
 #[async_trait]
 impl ToolHandler for ModuleOverviewHandler {
     fn metadata(&self) -> Option<pmcp::ToolInfo> {
-        Some(super::tool_info(
+        Some(super::library_tool_info(
+            &self.toolkit,
             "module_overview",
             "List all public items in a crate or module with brief descriptions",
             json!({
@@ -29,8 +65,9 @@ impl ToolHandler for ModuleOverviewHandler {
                     },
                     "limit": {
                         "type": "integer",
-                        "description": "Maximum items to return (default 50)"
-                    }
+                        "description": "Maximum items to return (default 50, capped by server limits)"
+                    },
+                    "workspace": super::workspace_property()
                 },
                 "required": ["crate"]
             }),
@@ -38,102 +75,56 @@ impl ToolHandler for ModuleOverviewHandler {
     }
 
     async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
-        let crate_name = args["crate"]
-            .as_str()
-            .ok_or_else(|| McpError::invalid_params("crate is required"))?;
-
-        let module_path = args.get("module").and_then(|v| v.as_str());
-        let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(50) as usize;
-
-        let crate_info = self.state.index.get_crate(crate_name);
-        let Some(crate_info) = crate_info else {
-            let available: Vec<_> = self
-                .state
-                .index
-                .crates
-                .iter()
-                .map(|c| {
-                    if let Some(ref alias) = c.alias {
-                        format!("{} ({})", c.name, alias)
-                    } else {
-                        c.name.clone()
-                    }
-                })
-                .collect();
-            return Ok(json!({
-                "error": format!("Crate '{crate_name}' not found"),
-                "available_crates": available,
-            }));
+        let request: ModuleOverviewRequest = super::parse_args(args)?;
+        let toolkit = match self.toolkit.workspace(request.workspace.as_deref()).await {
+            Ok(toolkit) => toolkit,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
         };
+        let crate_name = request.crate_name.as_str();
+        let limit =
+            super::limits::result_limit(request.limit, 50, &toolkit.manifest().server.limits)?;
 
-        let target_modules: Vec<&ModuleInfo> = if let Some(path) = module_path {
-            find_module(&crate_info.modules, path)
-                .map(|m| vec![m])
-                .unwrap_or_default()
-        } else {
-            crate_info.modules.iter().collect()
+        let view = match toolkit.module(crate_name, request.module.as_deref()) {
+            Ok(view) => view,
+            Err(e @ LookupError::CrateNotFound(_)) => {
+                return Ok(json!({
+                    "error": e.to_string(),
+                    "available_crates": toolkit.crate_labels(),
+                }));
+            }
+            Err(e) => return Ok(json!({"error": e.to_string()})),
         };
+        let module = view.module;
 
-        if target_modules.is_empty() {
-            return Ok(
-                json!({"error": format!("Module '{module_path:?}' not found in crate '{crate_name}'")}),
-            );
-        }
-
-        let module = target_modules[0];
-        let items: Vec<Value> = module
+        let items = module
             .items
             .iter()
             .take(limit)
-            .map(|item| {
-                json!({
-                    "kind": super::api_search::kind_label(&item.kind),
-                    "name": item.name,
-                    "signature": item.signature,
-                    "doc_summary": display::first_sentence(&item.doc_comment),
-                })
+            .map(|item| ModuleItem {
+                kind: kind_label(&item.kind),
+                name: &item.name,
+                signature: &item.signature,
+                doc_summary: display::first_sentence(&item.doc_comment),
             })
             .collect();
 
-        let submodules: Vec<Value> = module
+        let submodules = module
             .submodules
             .iter()
-            .map(|sub| {
-                json!({
-                    "name": sub.name,
-                    "item_count": sub.items.len(),
-                    "feature_gate": sub.feature_gate,
-                })
+            .map(|sub| SubmoduleSummary {
+                name: &sub.name,
+                item_count: sub.items.len(),
+                feature_gate: sub.feature_gate.as_deref(),
             })
             .collect();
 
-        Ok(json!({
-            "crate": crate_name,
-            "module": module.name,
-            "module_docs": module.module_docs,
-            "feature_gate": crate_info.feature_gate,
-            "items": items,
-            "submodules": submodules,
-        }))
-    }
-}
-
-fn find_module<'a>(modules: &'a [ModuleInfo], path: &str) -> Option<&'a ModuleInfo> {
-    let parts: Vec<&str> = path.split("::").collect();
-    find_module_recursive(modules, &parts)
-}
-
-fn find_module_recursive<'a>(modules: &'a [ModuleInfo], parts: &[&str]) -> Option<&'a ModuleInfo> {
-    if parts.is_empty() {
-        return None;
-    }
-    for module in modules {
-        if module.name == parts[0] {
-            if parts.len() == 1 {
-                return Some(module);
-            }
-            return find_module_recursive(&module.submodules, &parts[1..]);
-        }
+        super::respond(&ModuleOverviewResponse {
+            crate_name,
+            module: &module.name,
+            module_docs: &module.module_docs,
+            feature_gate: view.crate_info.feature_gate.as_deref(),
+            items,
+            submodules,
+        })
     }
-    None
 }
@@ -0,0 +1,279 @@
+//! Tool plugins compiled into a downstream binary.
+//!
+//! A program embedding this crate implements [`ToolPlugin`] for each set of
+//! extra tools, adds them to a [`PluginRegistry`], and passes that to
+//! [`super::build_tools_with_plugins`]. Only plugins named in the manifest's
+//! `[server.plugins]` table are installed, and their tools are namespaced as
+//! `<prefix>_<tool>` so they can't shadow built-in tools or each other.
+
+use super::catalog::{self, Implementation};
+use super::registry::ToolRegistry;
+use super::SharedState;
+use crate::config::is_valid_prefix;
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler, ToolInfo};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A named set of tools that can be installed into the server.
+pub trait ToolPlugin: Send + Sync {
+    /// Name selecting the plugin in `[server.plugins]`.
+    fn name(&self) -> &str;
+
+    /// Register the plugin's tools under their unprefixed names. `settings`
+    /// is the plugin's `[server.plugins.<name>.settings]` table.
+    fn register(
+        &self,
+        state: &Arc<SharedState>,
+        settings: &toml::Table,
+        tools: &mut ToolRegistry,
+    ) -> anyhow::Result<()>;
+
+    /// Implementation status of one of the plugin's tools, by unprefixed
+    /// name, shown in its description and by `capability_matrix`. Tools
+    /// without one are reported as `unreported`.
+    fn implementation(&self, _tool: &str) -> Option<Implementation> {
+        None
+    }
+}
+
+/// What [`PluginRegistry::install`] produced.
+#[derive(Default)]
+pub struct InstalledPlugins {
+    /// Tools under their prefixed names.
+    pub tools: ToolRegistry,
+    /// Statuses the plugins reported, by prefixed tool name.
+    pub implementations: HashMap<String, Implementation>,
+    /// Plugins and tools that were skipped, and why.
+    pub problems: Vec<String>,
+}
+
+/// Plugins available to the server, installed when the manifest names them.
+#[derive(Clone, Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Arc<dyn ToolPlugin>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, plugin: impl ToolPlugin + 'static) {
+        self.plugins.push(Arc::new(plugin));
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|p| p.name()).collect()
+    }
+
+    /// Build the tools of every configured plugin, prefixed. A plugin that is
+    /// unknown, has an invalid prefix, or fails to register is skipped, as is
+    /// any tool whose prefixed name is already taken by `existing`; each of
+    /// these is returned as a problem.
+    pub fn install(&self, state: &Arc<SharedState>, existing: &[&str]) -> InstalledPlugins {
+        let mut installed = InstalledPlugins::default();
+        let problems = &mut installed.problems;
+
+        let mut configured: Vec<_> = state.manifest.server.plugins.iter().collect();
+        configured.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, config) in configured {
+            let Some(plugin) = self.plugins.iter().find(|p| p.name() == name) else {
+                problems.push(format!("Unknown plugin '{name}'"));
+                continue;
+            };
+            let prefix = config.prefix(name);
+            if !is_valid_prefix(prefix) {
+                problems.push(format!("Plugin '{name}' has invalid prefix '{prefix}'"));
+                continue;
+            }
+
+            let mut tools = ToolRegistry::new();
+            if let Err(e) = plugin.register(state, &config.settings, &mut tools) {
+                problems.push(format!("Plugin '{name}' failed to register: {e}"));
+                continue;
+            }
+
+            for (tool, handler) in tools.iter() {
+                let prefixed = format!("{prefix}_{tool}");
+                if existing.contains(&prefixed.as_str()) || installed.tools.get(&prefixed).is_some()
+                {
+                    problems.push(format!(
+                        "Plugin '{name}' tool '{prefixed}' collides with an existing tool"
+                    ));
+                    continue;
+                }
+                let implementation = plugin.implementation(tool);
+                if let Some(implementation) = implementation {
+                    installed
+                        .implementations
+                        .insert(prefixed.clone(), implementation);
+                }
+                installed.tools.register(
+                    &prefixed,
+                    PrefixedHandler {
+                        name: prefixed.clone(),
+                        inner: handler.clone(),
+                        implementation,
+                    },
+                );
+            }
+        }
+        installed
+    }
+}
+
+/// A plugin tool published under its prefixed name.
+struct PrefixedHandler {
+    name: String,
+    inner: Arc<dyn ToolHandler>,
+    implementation: Option<Implementation>,
+}
+
+#[async_trait]
+impl ToolHandler for PrefixedHandler {
+    fn metadata(&self) -> Option<ToolInfo> {
+        self.inner.metadata().map(|mut info| {
+            info.name = self.name.clone();
+            if let (Some(implementation), Some(description)) =
+                (self.implementation, info.description.as_mut())
+            {
+                *description = catalog::describe_status(description, implementation);
+            }
+            info
+        })
+    }
+
+    async fn handle(&self, args: Value, extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        self.inner.handle(args, extra).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LibraryManifest;
+    use crate::parser::index::{ApiIndex, Unvalidated};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct EchoRequest {
+        message: String,
+    }
+
+    struct EchoPlugin;
+
+    impl ToolPlugin for EchoPlugin {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn register(
+            &self,
+            _state: &Arc<SharedState>,
+            settings: &toml::Table,
+            tools: &mut ToolRegistry,
+        ) -> anyhow::Result<()> {
+            let suffix = settings
+                .get("suffix")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            tools.register_fn(
+                "say",
+                "Echo a message",
+                json!({"type": "object", "properties": {"message": {"type": "string"}}}),
+                move |request: EchoRequest, _extra| {
+                    let reply = format!("{}{suffix}", request.message);
+                    async move { Ok(json!({ "reply": reply })) }
+                },
+            );
+            Ok(())
+        }
+
+        fn implementation(&self, tool: &str) -> Option<Implementation> {
+            (tool == "say").then_some(Implementation {
+                status: catalog::Status::Stub,
+                limitations: &["Repeats the message verbatim"],
+            })
+        }
+    }
+
+    fn state(plugins: &str) -> Arc<SharedState> {
+        let toml_str = format!(
+            r#"
+[library]
+name = "testlib"
+display_name = "Test"
+version = "0.1.0"
+description = "Test"
+source_path = "."
+
+[workspace]
+root_cargo_toml = "Cargo.toml"
+umbrella_crate = "src/lib.rs"
+
+[crates.default]
+members = []
+
+[crates.optional]
+
+[aliases]
+
+{plugins}
+"#
+        );
+        let manifest: LibraryManifest = toml::from_str(&toml_str).unwrap();
+        SharedState::new(
+            ApiIndex::<Unvalidated>::empty().validate().unwrap(),
+            manifest,
+        )
+    }
+
+    fn plugins() -> PluginRegistry {
+        let mut plugins = PluginRegistry::new();
+        plugins.register(EchoPlugin);
+        plugins
+    }
+
+    #[tokio::test]
+    async fn installs_configured_plugins_under_prefix() {
+        let state = state("[server.plugins.echo]\nprefix = \"ex\"\n\n[server.plugins.echo.settings]\nsuffix = \"!\"");
+        let InstalledPlugins {
+            tools,
+            implementations,
+            problems,
+        } = plugins().install(&state, &["api_search"]);
+        assert!(problems.is_empty());
+        assert_eq!(tools.names(), vec!["ex_say"]);
+        let info = tools.get("ex_say").unwrap().metadata().unwrap();
+        assert_eq!(info.name, "ex_say");
+        assert!(info.description.unwrap().contains("Status: stub."));
+        assert_eq!(implementations["ex_say"].status, catalog::Status::Stub);
+
+        let reply = tools
+            .call_local("ex_say", json!({"message": "hi"}))
+            .await
+            .unwrap();
+        assert_eq!(reply, json!({"reply": "hi!"}));
+    }
+
+    #[test]
+    fn skips_unconfigured_unknown_and_colliding_plugins() {
+        let installed = plugins().install(&state(""), &[]);
+        assert!(installed.tools.is_empty());
+        assert!(installed.problems.is_empty());
+
+        let installed = plugins().install(&state("[server.plugins.nope]"), &[]);
+        assert!(installed.tools.is_empty());
+        assert_eq!(installed.problems, vec!["Unknown plugin 'nope'"]);
+
+        let installed = plugins().install(&state("[server.plugins.echo]"), &["echo_say"]);
+        assert!(installed.tools.is_empty());
+        assert!(installed.implementations.is_empty());
+        assert!(installed.problems[0].contains("collides"));
+    }
+}
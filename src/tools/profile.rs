@@ -0,0 +1,105 @@
+use crate::config::{ServerConfig, ToolProfile};
+
+/// Tools included in the `minimal` profile.
+const MINIMAL_TOOLS: &[&str] = &["api_search", "type_info", "server_info"];
+
+/// Tools included in the `reference` profile.
+const REFERENCE_TOOLS: &[&str] = &[
+    "api_search",
+    "type_info",
+    "module_overview",
+    "feature_map",
+    "dependency_graph",
+    "browse_docs",
+    "usage_examples",
+    "resolve_features",
+    "explain_error",
+    "search_docs",
+    "changelog",
+    "api_diff",
+    "upgrade_advice",
+    "capability_matrix",
+    "server_info",
+];
+
+/// Whether a profile includes the named tool.
+pub fn profile_includes(profile: ToolProfile, tool: &str) -> bool {
+    match profile {
+        ToolProfile::Minimal => MINIMAL_TOOLS.contains(&tool),
+        ToolProfile::Reference => REFERENCE_TOOLS.contains(&tool),
+        ToolProfile::Full => true,
+    }
+}
+
+/// Whether the server configuration enables the named tool: the profile,
+/// plus `enable_tools`, minus `disable_tools`.
+pub fn tool_enabled(config: &ServerConfig, tool: &str) -> bool {
+    if config.disable_tools.iter().any(|t| t == tool) {
+        return false;
+    }
+    profile_includes(config.profile, tool) || config.enable_tools.iter().any(|t| t == tool)
+}
+
+/// Names in `enable_tools`/`disable_tools` that don't match a known tool.
+pub fn unknown_tool_names<'a>(config: &'a ServerConfig, known: &[&str]) -> Vec<&'a str> {
+    config
+        .enable_tools
+        .iter()
+        .chain(&config.disable_tools)
+        .map(String::as_str)
+        .filter(|name| !known.contains(name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(profile: ToolProfile) -> ServerConfig {
+        ServerConfig {
+            profile,
+            ..ServerConfig::default()
+        }
+    }
+
+    #[test]
+    fn profiles_nest() {
+        assert!(profile_includes(ToolProfile::Minimal, "api_search"));
+        assert!(!profile_includes(ToolProfile::Minimal, "browse_docs"));
+        assert!(profile_includes(ToolProfile::Minimal, "server_info"));
+        assert!(profile_includes(ToolProfile::Reference, "browse_docs"));
+        assert!(profile_includes(ToolProfile::Reference, "search_docs"));
+        assert!(profile_includes(ToolProfile::Reference, "upgrade_advice"));
+        assert!(!profile_includes(ToolProfile::Reference, "batch_call"));
+        assert!(profile_includes(ToolProfile::Full, "batch_call"));
+    }
+
+    #[test]
+    fn enable_and_disable_lists_override_profile() {
+        let mut config = config(ToolProfile::Minimal);
+        config.enable_tools.push("browse_docs".to_string());
+        config.disable_tools.push("type_info".to_string());
+
+        assert!(tool_enabled(&config, "api_search"));
+        assert!(tool_enabled(&config, "browse_docs"));
+        assert!(!tool_enabled(&config, "type_info"));
+    }
+
+    #[test]
+    fn disable_wins_over_enable() {
+        let mut config = config(ToolProfile::Full);
+        config.enable_tools.push("batch_call".to_string());
+        config.disable_tools.push("batch_call".to_string());
+        assert!(!tool_enabled(&config, "batch_call"));
+    }
+
+    #[test]
+    fn reports_unknown_tool_names() {
+        let mut config = config(ToolProfile::Full);
+        config.disable_tools.push("api_serach".to_string());
+        assert_eq!(
+            unknown_tool_names(&config, &["api_search"]),
+            vec!["api_serach"]
+        );
+    }
+}
@@ -0,0 +1,477 @@
+use super::coerce;
+use super::drain::InFlight;
+use super::fn_tool::FnToolHandler;
+use super::limits;
+use super::session::CallRecorder;
+use super::throttle::Throttle;
+use super::usage::{self, CallOutcome, UsageStats};
+use super::validate;
+use super::verbosity::Verbosity;
+use async_trait::async_trait;
+use pmcp::{Error as McpError, ErrorCode, RequestHandlerExtra, ToolHandler, ToolInfo};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+/// Ordered collection of tool handlers addressable by name.
+///
+/// The MCP server registers every entry with pmcp, and tools that dispatch
+/// to other tools (such as `batch_call`) look handlers up here.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: Vec<(String, Arc<dyn ToolHandler>)>,
+    policy: CallPolicy,
+}
+
+/// Cross-cutting behaviour applied to every call dispatched through a
+/// registry. Registries used internally by composite tools leave it empty so
+/// nested calls aren't throttled or recorded twice.
+#[derive(Clone, Default)]
+pub struct CallPolicy {
+    pub throttle: Option<Arc<Throttle>>,
+    pub recorder: Option<Arc<CallRecorder>>,
+    pub stats: Option<Arc<UsageStats>>,
+    pub in_flight: Option<Arc<InFlight>>,
+    /// Largest response returned to the caller, in serialized bytes.
+    pub max_response_bytes: Option<usize>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply rate limiting and concurrency caps to every call made through
+    /// this registry.
+    pub fn with_throttle(mut self, throttle: Arc<Throttle>) -> Self {
+        self.policy.throttle = Some(throttle);
+        self
+    }
+
+    /// Record every successful call made through this registry.
+    pub fn with_recorder(mut self, recorder: Arc<CallRecorder>) -> Self {
+        self.policy.recorder = Some(recorder);
+        self
+    }
+
+    /// Collect usage statistics for every call made through this registry.
+    pub fn with_stats(mut self, stats: Arc<UsageStats>) -> Self {
+        self.policy.stats = Some(stats);
+        self
+    }
+
+    /// The statistics collector attached with [`with_stats`](Self::with_stats).
+    pub fn stats(&self) -> Option<&Arc<UsageStats>> {
+        self.policy.stats.as_ref()
+    }
+
+    /// Track running calls so they can be drained at shutdown.
+    pub fn with_in_flight(mut self, in_flight: Arc<InFlight>) -> Self {
+        self.policy.in_flight = Some(in_flight);
+        self
+    }
+
+    /// The tracker attached with [`with_in_flight`](Self::with_in_flight).
+    pub fn in_flight(&self) -> Option<&Arc<InFlight>> {
+        self.policy.in_flight.as_ref()
+    }
+
+    /// Replace responses larger than `max_bytes` with an error.
+    pub fn with_response_limit(mut self, max_bytes: usize) -> Self {
+        self.policy.max_response_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Add a handler under the given tool name.
+    pub fn register(&mut self, name: &str, handler: impl ToolHandler + 'static) {
+        self.tools.push((name.to_string(), Arc::new(handler)));
+    }
+
+    /// Add a tool defined by one async function taking its typed request.
+    /// See [`FnToolHandler`].
+    pub fn register_fn<Req, F, Fut>(
+        &mut self,
+        name: &str,
+        description: &str,
+        input_schema: Value,
+        handler: F,
+    ) where
+        Req: DeserializeOwned + Send + 'static,
+        F: Fn(Req, RequestHandlerExtra) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, McpError>> + Send + 'static,
+    {
+        self.register(
+            name,
+            FnToolHandler::new(name, description, input_schema, handler),
+        );
+    }
+
+    /// Append every tool of `other`, keeping this registry's call policy.
+    pub fn extend(&mut self, other: ToolRegistry) {
+        self.tools.extend(other.tools);
+    }
+
+    /// Look up a handler by tool name.
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn ToolHandler>> {
+        self.tools
+            .iter()
+            .find(|(tool_name, _)| tool_name == name)
+            .map(|(_, handler)| handler)
+    }
+
+    /// Keep only the tools for which `keep` returns true.
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.tools.retain(|(name, _)| keep(name));
+    }
+
+    /// Registered tool names, in registration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.tools.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Arc<dyn ToolHandler>)> {
+        self.tools
+            .iter()
+            .map(|(name, handler)| (name.as_str(), handler))
+    }
+
+    /// Adapters for registering every tool with the pmcp server builder.
+    pub fn shared_handlers(&self) -> Vec<SharedHandler> {
+        self.tools
+            .iter()
+            .map(|(name, handler)| SharedHandler {
+                name: name.clone(),
+                handler: handler.clone(),
+                policy: self.policy.clone(),
+            })
+            .collect()
+    }
+
+    /// Invoke a registered tool by name.
+    pub async fn call(
+        &self,
+        name: &str,
+        args: Value,
+        extra: RequestHandlerExtra,
+    ) -> Result<Value, McpError> {
+        let handler = self
+            .get(name)
+            .ok_or_else(|| McpError::invalid_params(format!("Unknown tool '{name}'")))?;
+        dispatch(name, handler.clone(), &self.policy, args, extra).await
+    }
+
+    /// Invoke a tool from inside the process (CLI commands, tests) rather
+    /// than on behalf of an MCP request.
+    pub async fn call_local(&self, name: &str, args: Value) -> Result<Value, McpError> {
+        self.call(name, args, local_extra()).await
+    }
+}
+
+/// Request context for calls that don't originate from an MCP client.
+pub fn local_extra() -> RequestHandlerExtra {
+    RequestHandlerExtra::new("local".to_string(), CancellationToken::new())
+}
+
+/// Run a handler inside a `tool_call` span carrying the request and session
+/// ids, so every log line emitted by the tool can be correlated to a client,
+/// applying the registry's call policy around it.
+async fn dispatch(
+    name: &str,
+    handler: Arc<dyn ToolHandler>,
+    policy: &CallPolicy,
+    args: Value,
+    extra: RequestHandlerExtra,
+) -> Result<Value, McpError> {
+    let span = tracing::info_span!(
+        "tool_call",
+        tool = name,
+        request_id = ?extra.request_id,
+        session_id = ?extra.session_id,
+    );
+
+    async move {
+        let mut args = args;
+        let schema = handler.metadata().map(|info| info.input_schema);
+        let warnings = match &schema {
+            Some(schema) => coerce::coerce_args(&mut args, schema),
+            None => Vec::new(),
+        };
+        if !warnings.is_empty() {
+            tracing::debug!(?warnings, "coerced tool arguments");
+        }
+
+        let shape = policy.stats.as_ref().map(|_| usage::argument_shape(&args));
+
+        if let Some(schema) = &schema {
+            let violations = validate::validate_args(&args, schema);
+            if !violations.is_empty() {
+                let message = validate::error_message(name, &violations, schema);
+                if let (Some(stats), Some(shape)) = (&policy.stats, shape) {
+                    stats.record(name, shape, 0, CallOutcome::Invalid(&message));
+                }
+                return Err(McpError::invalid_params(message));
+            }
+        }
+
+        let _in_flight = match &policy.in_flight {
+            Some(in_flight) => Some(
+                in_flight
+                    .begin(extra.cancellation_token.clone())
+                    .map_err(|e| McpError::protocol(ErrorCode::INVALID_REQUEST, e))?,
+            ),
+            None => None,
+        };
+
+        let _permit = match &policy.throttle {
+            Some(throttle) => match throttle.acquire(name).await {
+                Ok(permit) => Some(permit),
+                Err(e) => {
+                    tracing::warn!("{e}");
+                    if let (Some(stats), Some(shape)) = (&policy.stats, shape) {
+                        stats.record(name, shape, 0, CallOutcome::Rejected(&e));
+                    }
+                    return Err(McpError::protocol(ErrorCode::INVALID_REQUEST, e));
+                }
+            },
+            None => None,
+        };
+
+        let recorded_args = policy.recorder.as_ref().map(|_| args.clone());
+        let verbosity = match Verbosity::take(&mut args) {
+            Ok(verbosity) => verbosity,
+            Err(e) => {
+                if let (Some(stats), Some(shape)) = (&policy.stats, shape) {
+                    stats.record(name, shape, 0, CallOutcome::Invalid(&e));
+                }
+                return Err(McpError::invalid_params(e));
+            }
+        };
+
+        let started = Instant::now();
+        let mut result = run_isolated(name, handler, args, extra).await;
+        if let (false, Ok(Value::Object(response))) = (warnings.is_empty(), &mut result) {
+            response.insert("warnings".to_string(), Value::from(warnings));
+        }
+        if let Ok(response) = &mut result {
+            verbosity.apply(response);
+        }
+        if let (Some(max_bytes), Ok(response)) = (policy.max_response_bytes, &result) {
+            if let Err(e) = limits::check_response_size(name, response, max_bytes) {
+                result = Err(e);
+            }
+        }
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(_) => tracing::debug!(elapsed_ms, "tool call completed"),
+            Err(e) => tracing::warn!(elapsed_ms, error = %e, "tool call failed"),
+        }
+
+        if let (Some(stats), Some(shape)) = (&policy.stats, shape) {
+            let error = match &result {
+                Ok(response) => response
+                    .get("error")
+                    .map(|e| e.as_str().map_or_else(|| e.to_string(), String::from)),
+                Err(e) => Some(e.to_string()),
+            };
+            let outcome = match &error {
+                Some(reason) => CallOutcome::Failed(reason),
+                None => CallOutcome::Succeeded,
+            };
+            stats.record(name, shape, elapsed_ms, outcome);
+        }
+
+        if let (Some(recorder), Some(args), Ok(_)) = (&policy.recorder, recorded_args, &result) {
+            recorder.record(name, args);
+        }
+        result
+    }
+    .instrument(span)
+    .await
+}
+
+/// Run a handler on its own task so a panic inside it becomes an internal
+/// error for that call instead of taking down the server.
+async fn run_isolated(
+    name: &str,
+    handler: Arc<dyn ToolHandler>,
+    args: Value,
+    extra: RequestHandlerExtra,
+) -> Result<Value, McpError> {
+    let task = tokio::spawn(
+        async move { handler.handle(args, extra).await }.instrument(tracing::Span::current()),
+    );
+    // Abort the task if the caller stops waiting, as an inline call would be.
+    let mut task = AbortOnDrop(task);
+    match (&mut task.0).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => {
+            let message = panic_message(e.into_panic());
+            tracing::error!(panic = %message, "tool handler panicked");
+            Err(McpError::internal(format!(
+                "Tool '{name}' failed with an internal error: {message}"
+            )))
+        }
+        Err(e) => Err(McpError::internal(format!(
+            "Tool '{name}' was aborted: {e}"
+        ))),
+    }
+}
+
+/// A spawned task that is aborted when the handle is dropped, so work
+/// started for a caller ends when the caller stops waiting.
+pub(crate) struct AbortOnDrop<T>(pub(crate) tokio::task::JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic with non-string payload".to_string())
+}
+
+/// Adapter so a registry entry can be handed to the pmcp server builder,
+/// which takes handlers by value.
+pub struct SharedHandler {
+    name: String,
+    handler: Arc<dyn ToolHandler>,
+    policy: CallPolicy,
+}
+
+impl SharedHandler {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[async_trait]
+impl ToolHandler for SharedHandler {
+    fn metadata(&self) -> Option<ToolInfo> {
+        self.handler.metadata()
+    }
+
+    async fn handle(&self, args: Value, extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        dispatch(&self.name, self.handler.clone(), &self.policy, args, extra).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct EchoHandler;
+
+    #[async_trait]
+    impl ToolHandler for EchoHandler {
+        async fn handle(
+            &self,
+            args: Value,
+            _extra: RequestHandlerExtra,
+        ) -> Result<Value, McpError> {
+            Ok(args)
+        }
+    }
+
+    struct PanicHandler;
+
+    #[async_trait]
+    impl ToolHandler for PanicHandler {
+        async fn handle(
+            &self,
+            _args: Value,
+            _extra: RequestHandlerExtra,
+        ) -> Result<Value, McpError> {
+            panic!("index out of bounds");
+        }
+    }
+
+    #[tokio::test]
+    async fn panicking_tool_returns_internal_error() {
+        let mut registry = ToolRegistry::new();
+        registry.register("boom", PanicHandler);
+        registry.register("echo", EchoHandler);
+
+        let err = registry.call_local("boom", json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("index out of bounds"));
+
+        let echoed = registry.call_local("echo", json!({"ok": true})).await;
+        assert_eq!(echoed.unwrap(), json!({"ok": true}));
+    }
+
+    #[test]
+    fn registry_preserves_registration_order() {
+        let mut registry = ToolRegistry::new();
+        registry.register("b_tool", EchoHandler);
+        registry.register("a_tool", EchoHandler);
+
+        assert_eq!(registry.names(), vec!["b_tool", "a_tool"]);
+        assert_eq!(registry.len(), 2);
+        assert!(!registry.is_empty());
+    }
+
+    #[test]
+    fn registry_lookup_by_name() {
+        let mut registry = ToolRegistry::new();
+        registry.register("echo", EchoHandler);
+
+        assert!(registry.get("echo").is_some());
+        assert!(registry.get("missing").is_none());
+    }
+
+    #[test]
+    fn retain_removes_filtered_tools() {
+        let mut registry = ToolRegistry::new();
+        registry.register("keep", EchoHandler);
+        registry.register("drop", EchoHandler);
+        registry.retain(|name| name != "drop");
+        assert_eq!(registry.names(), vec!["keep"]);
+    }
+
+    #[tokio::test]
+    async fn invalid_calls_are_left_out_of_latency() {
+        let mut registry = ToolRegistry::new().with_stats(Arc::new(UsageStats::new()));
+        registry.register_fn(
+            "lookup",
+            "Look up a name",
+            json!({
+                "type": "object",
+                "properties": {"name": {"type": "string"}},
+                "required": ["name"]
+            }),
+            |request: serde_json::Map<String, Value>, _extra| async move {
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                Ok(Value::Object(request))
+            },
+        );
+
+        assert!(registry.call_local("lookup", json!({})).await.is_err());
+        assert!(registry
+            .call_local("lookup", json!({"name": "x"}))
+            .await
+            .is_ok());
+
+        let usage = registry.stats().unwrap().snapshot(Some("lookup"))["lookup"].clone();
+        assert_eq!(usage["calls"], 2);
+        assert_eq!(usage["invalid"], 1);
+        assert_eq!(usage["failures"], 0);
+        assert!(usage["mean_ms"].as_u64().unwrap() >= 20, "{usage}");
+    }
+}
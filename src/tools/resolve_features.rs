@@ -0,0 +1,112 @@
+use crate::parser::workspace::ResolvedCapability;
+use crate::toolkit::AmariToolkit;
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub struct ResolveFeaturesHandler {
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveFeaturesRequest {
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolveFeaturesResponse<'a> {
+    pub package: &'a str,
+    pub version: &'a str,
+    pub default_features: bool,
+    pub features: &'a [String],
+    pub resolved: Vec<ResolvedView<'a>>,
+    pub unresolved: Vec<UnresolvedView<'a>>,
+    pub cargo_toml: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolvedView<'a> {
+    pub capability: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub implies: &'a [String],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub crates: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnresolvedView<'a> {
+    pub capability: &'a str,
+    pub candidates: &'a [String],
+}
+
+#[async_trait]
+impl ToolHandler for ResolveFeaturesHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::library_tool_info(
+            &self.toolkit,
+            "resolve_features",
+            "Map desired capabilities (gpu, wasm, no_std, ...) to the library's declared Cargo features and return the dependency stanza enabling them",
+            json!({
+                "type": "object",
+                "properties": {
+                    "capabilities": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Capabilities or feature names, e.g. [\"gpu\", \"no_std\"]"
+                    }
+                },
+                "required": ["capabilities"]
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let toolkit = self.toolkit.current();
+        let request: ResolveFeaturesRequest = super::parse_args(args)?;
+        let limits = &toolkit.manifest().server.limits;
+        for capability in &request.capabilities {
+            super::limits::check_query("capabilities", capability, limits)?;
+        }
+
+        let resolution = match toolkit.resolve_features(&request.capabilities) {
+            Ok(resolution) => resolution,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
+        };
+
+        super::respond(&ResolveFeaturesResponse {
+            package: &resolution.package,
+            version: &resolution.version,
+            default_features: resolution.default_features,
+            features: &resolution.features,
+            resolved: resolution.resolved.iter().map(resolved_view).collect(),
+            unresolved: resolution
+                .unresolved
+                .iter()
+                .map(|u| UnresolvedView {
+                    capability: &u.capability,
+                    candidates: &u.candidates,
+                })
+                .collect(),
+            cargo_toml: &resolution.stanza,
+        })
+    }
+}
+
+fn resolved_view(resolved: &ResolvedCapability) -> ResolvedView<'_> {
+    ResolvedView {
+        capability: &resolved.capability,
+        feature: resolved.feature.as_deref(),
+        implies: &resolved.implies,
+        crates: &resolved.crates,
+        note: resolved
+            .feature
+            .is_none()
+            .then_some("disables default features, which enable `std`"),
+    }
+}
@@ -0,0 +1,149 @@
+use crate::toolkit::{kind_label, AmariToolkit, DocMatch, DocTarget, SearchMode};
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub struct SearchDocsHandler {
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchDocsRequest {
+    pub query: String,
+    #[serde(rename = "crate")]
+    pub crate_name: Option<String>,
+    #[serde(default)]
+    pub mode: Mode,
+    pub limit: Option<u64>,
+    pub workspace: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    #[default]
+    Keyword,
+    Semantic,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchDocsResponse<'a> {
+    pub query: &'a str,
+    pub mode: Mode,
+    /// Embedding provider used in `semantic` mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<&'a str>,
+    pub results: Vec<DocResult<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DocResult<'a> {
+    /// Item kind label, or `module`.
+    pub kind: &'static str,
+    pub path: String,
+    /// BM25 relevance in `keyword` mode, cosine similarity in `semantic`
+    /// mode; higher is better.
+    pub score: f64,
+    pub snippet: &'a str,
+    pub source_file: String,
+}
+
+#[async_trait]
+impl ToolHandler for SearchDocsHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::library_tool_info(
+            &self.toolkit,
+            "search_docs",
+            "Search doc comments and module docs by keywords, or by a natural-language question in semantic mode, ranked by relevance with a snippet around each match",
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Words to look for, e.g. \"normalize rotor\""
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["keyword", "semantic"],
+                        "description": "keyword (default): exact words, BM25-ranked. semantic: embedding similarity, for questions like \"which function normalizes a rotor\""
+                    },
+                    "crate": {
+                        "type": "string",
+                        "description": "Only search this crate (name or alias)"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum results (default 10, capped by server limits)"
+                    },
+                    "workspace": super::workspace_property()
+                },
+                "required": ["query"]
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let request: SearchDocsRequest = super::parse_args(args)?;
+        super::limits::check_query(
+            "query",
+            &request.query,
+            &self.toolkit.manifest().server.limits,
+        )?;
+        let toolkit = match self.toolkit.workspace(request.workspace.as_deref()).await {
+            Ok(toolkit) => toolkit,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
+        };
+        let limits = &toolkit.manifest().server.limits;
+        let limit = super::limits::result_limit(request.limit, 10, limits)?;
+
+        let mode = match request.mode {
+            Mode::Keyword => SearchMode::Keyword,
+            Mode::Semantic => SearchMode::Semantic,
+        };
+        let matches =
+            match toolkit.search_docs(&request.query, mode, request.crate_name.as_deref(), limit) {
+                Ok(matches) => matches,
+                Err(e) => return Ok(json!({ "error": e.to_string() })),
+            };
+        if matches.is_empty() {
+            return Ok(json!({
+                "error": format!("No documentation matches '{}'", request.query)
+            }));
+        }
+
+        super::respond(&SearchDocsResponse {
+            query: &request.query,
+            mode: request.mode,
+            provider: matches!(request.mode, Mode::Semantic)
+                .then(|| toolkit.state().embeddings.name()),
+            results: matches.iter().map(result).collect(),
+        })
+    }
+}
+
+fn result<'a>(found: &'a DocMatch<'a>) -> DocResult<'a> {
+    let (kind, path, source_file) = match &found.target {
+        DocTarget::Item(item) => (
+            kind_label(&item.kind),
+            item.full_path.clone(),
+            &item.source_file,
+        ),
+        DocTarget::Module { view, path } => {
+            // Module paths start at the crate's `lib` root, which reads as the crate itself.
+            let path = match path.strip_prefix("lib") {
+                Some(rest) => format!("{}{rest}", view.crate_info.name),
+                None => format!("{}::{path}", view.crate_info.name),
+            };
+            ("module", path, &view.module.path)
+        }
+    };
+    DocResult {
+        kind,
+        path,
+        score: (found.score * 1000.0).round() / 1000.0,
+        snippet: &found.snippet,
+        source_file: source_file.display().to_string(),
+    }
+}
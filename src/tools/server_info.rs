@@ -0,0 +1,70 @@
+use super::SharedState;
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub struct ServerInfoHandler {
+    pub state: Arc<SharedState>,
+    /// Names of every tool registered with the server, including this one.
+    pub tools: Vec<String>,
+}
+
+#[async_trait]
+impl ToolHandler for ServerInfoHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::tool_info(
+            "server_info",
+            "Report server version, indexed library, registered tools, configured limits, and uptime",
+            json!({
+                "type": "object",
+                "properties": {}
+            }),
+        ))
+    }
+
+    async fn handle(&self, _args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let current = self.state.current();
+        let index = &current.index;
+        let library = &self.state.manifest.library;
+        let config = &self.state.manifest.server;
+        let stats = index.stats();
+
+        Ok(json!({
+            "status": "ok",
+            "server": {
+                "name": "amari-mcp",
+                "version": env!("CARGO_PKG_VERSION"),
+                "uptime_secs": self.state.started_at.elapsed().as_secs(),
+            },
+            "library": {
+                "name": library.name,
+                "version": library.version,
+                "crates": stats.crate_count,
+                "modules": stats.module_count,
+                "items": stats.item_count,
+                "parse_warnings": index.parse_errors.len(),
+            },
+            "source": {
+                "watching": config.watch_interval_secs.is_some(),
+                "index_stale": self.state.watch.is_stale(),
+                "changed_files": self.state.watch.changed(),
+                "reindexes": self.state.watch.reindexes(),
+            },
+            "tools": self.tools,
+            "profile": format!("{:?}", config.profile).to_lowercase(),
+            "limits": {
+                "max_concurrent_calls": config.max_concurrent_calls,
+                "tool_concurrency": config.tool_concurrency,
+                "rate_limit": config.rate_limit.as_ref().map(|r| json!({
+                    "calls_per_second": r.calls_per_second,
+                    "burst": r.burst,
+                })),
+                "max_results": config.limits.max_results,
+                "max_query_length": config.limits.max_query_length,
+                "max_batch_calls": config.limits.max_batch_calls,
+                "max_response_bytes": config.limits.max_response_bytes,
+            },
+        }))
+    }
+}
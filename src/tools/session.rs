@@ -0,0 +1,263 @@
+use super::registry::ToolRegistry;
+use super::SharedState;
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Most calls kept by the session recorder.
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// Version of the replay script format emitted by `record_session`.
+pub const SCRIPT_VERSION: u64 = 1;
+
+/// Tools that inspect or replay the session and are never themselves recorded.
+const UNRECORDED_TOOLS: &[&str] = &["record_session", "replay_script"];
+
+/// A tool call captured for replay.
+#[derive(Debug, Clone)]
+pub struct RecordedCall {
+    pub tool: String,
+    pub arguments: Value,
+}
+
+/// Bounded in-memory log of successful tool calls, oldest dropped first.
+pub struct CallRecorder {
+    calls: Mutex<VecDeque<RecordedCall>>,
+    capacity: usize,
+}
+
+impl CallRecorder {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            calls: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn record(&self, tool: &str, arguments: Value) {
+        if UNRECORDED_TOOLS.contains(&tool) {
+            return;
+        }
+        let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+        if calls.len() == self.capacity {
+            calls.pop_front();
+        }
+        calls.push_back(RecordedCall {
+            tool: tool.to_string(),
+            arguments,
+        });
+    }
+
+    /// Calls recorded so far, oldest first.
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        let calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+        calls.iter().cloned().collect()
+    }
+}
+
+/// Render recorded calls as a portable replay script.
+pub fn to_script(library: &str, calls: &[RecordedCall]) -> Value {
+    json!({
+        "version": SCRIPT_VERSION,
+        "library": library,
+        "calls": calls
+            .iter()
+            .map(|call| json!({"tool": call.tool, "arguments": call.arguments}))
+            .collect::<Vec<_>>(),
+    })
+}
+
+pub struct RecordSessionHandler {
+    pub state: Arc<SharedState>,
+    pub recorder: Arc<CallRecorder>,
+}
+
+#[async_trait]
+impl ToolHandler for RecordSessionHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::tool_info(
+            "record_session",
+            "Return the tool calls made so far in this session as a replayable JSON script",
+            json!({
+                "type": "object",
+                "properties": {}
+            }),
+        ))
+    }
+
+    async fn handle(&self, _args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        Ok(to_script(
+            &self.state.manifest.library.name,
+            &self.recorder.calls(),
+        ))
+    }
+}
+
+pub struct ReplayScriptHandler {
+    pub state: Arc<SharedState>,
+    pub tools: Arc<ToolRegistry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayScriptRequest {
+    /// Raw script; checked by [`parse_script`] so errors can name the entry.
+    pub script: Value,
+    #[serde(default)]
+    pub stop_on_error: bool,
+}
+
+#[async_trait]
+impl ToolHandler for ReplayScriptHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::tool_info(
+            "replay_script",
+            "Execute a script produced by record_session, in order, and return each call's result",
+            json!({
+                "type": "object",
+                "properties": {
+                    "script": {
+                        "type": "object",
+                        "description": "Script object with a `calls` array of {tool, arguments}"
+                    },
+                    "stop_on_error": {
+                        "type": "boolean",
+                        "description": "Stop at the first failing call (default false)"
+                    }
+                },
+                "required": ["script"]
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let request: ReplayScriptRequest = super::parse_args(args)?;
+        let calls = parse_script(
+            &request.script,
+            self.state.manifest.server.limits.max_batch_calls,
+        )
+        .map_err(McpError::invalid_params)?;
+
+        let mut results = Vec::with_capacity(calls.len());
+        let mut failed = 0;
+        for (index, call) in calls.into_iter().enumerate() {
+            match self
+                .tools
+                .call(&call.tool, call.arguments, extra.clone())
+                .await
+            {
+                Ok(result) => results.push(json!({
+                    "index": index,
+                    "tool": call.tool,
+                    "result": result,
+                })),
+                Err(e) => {
+                    failed += 1;
+                    results.push(json!({
+                        "index": index,
+                        "tool": call.tool,
+                        "error": e.to_string(),
+                    }));
+                    if request.stop_on_error {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(json!({
+            "results": results,
+            "executed": results.len(),
+            "failed": failed,
+        }))
+    }
+}
+
+/// Validate a replay script and extract its calls.
+pub fn parse_script(script: &Value, max_calls: usize) -> Result<Vec<RecordedCall>, String> {
+    if let Some(version) = script.get("version").and_then(|v| v.as_u64()) {
+        if version > SCRIPT_VERSION {
+            return Err(format!(
+                "script version {version} is newer than supported version {SCRIPT_VERSION}"
+            ));
+        }
+    }
+
+    let calls = script
+        .get("calls")
+        .and_then(|v| v.as_array())
+        .ok_or("script.calls must be an array")?;
+    if calls.len() > max_calls {
+        return Err(format!(
+            "script contains {} calls, maximum is {max_calls}",
+            calls.len()
+        ));
+    }
+
+    calls
+        .iter()
+        .enumerate()
+        .map(|(index, call)| {
+            let tool = call
+                .get("tool")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("script.calls[{index}].tool is required"))?;
+            Ok(RecordedCall {
+                tool: tool.to_string(),
+                arguments: call.get("arguments").cloned().unwrap_or_else(|| json!({})),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorder_keeps_most_recent_calls() {
+        let recorder = CallRecorder::new(2);
+        recorder.record("api_search", json!({"query": "a"}));
+        recorder.record("api_search", json!({"query": "b"}));
+        recorder.record("type_info", json!({"name": "C"}));
+
+        let calls = recorder.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].arguments["query"], "b");
+        assert_eq!(calls[1].tool, "type_info");
+    }
+
+    #[test]
+    fn recorder_skips_session_tools() {
+        let recorder = CallRecorder::new(10);
+        recorder.record("record_session", json!({}));
+        recorder.record("replay_script", json!({}));
+        assert!(recorder.calls().is_empty());
+    }
+
+    #[test]
+    fn script_round_trips() {
+        let calls = vec![RecordedCall {
+            tool: "feature_map".to_string(),
+            arguments: json!({"feature": "gpu"}),
+        }];
+        let script = to_script("amari", &calls);
+        assert_eq!(script["version"], SCRIPT_VERSION);
+
+        let parsed = parse_script(&script, 10).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].tool, "feature_map");
+        assert_eq!(parsed[0].arguments["feature"], "gpu");
+    }
+
+    #[test]
+    fn rejects_newer_versions_and_oversized_scripts() {
+        let newer = json!({"version": SCRIPT_VERSION + 1, "calls": []});
+        assert!(parse_script(&newer, 10).is_err());
+
+        let big = json!({"calls": [{"tool": "a"}, {"tool": "b"}]});
+        assert!(parse_script(&big, 1).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::config::{RateLimitConfig, ServerConfig};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Admission control for tool calls: a token-bucket rate limit plus a global
+/// and optional per-tool concurrency cap.
+///
+/// Rate-limited calls are rejected immediately; calls over a concurrency cap
+/// wait for a slot.
+pub struct Throttle {
+    global: Arc<Semaphore>,
+    per_tool: HashMap<String, Arc<Semaphore>>,
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+/// Slots held for the duration of a tool call.
+pub struct ThrottlePermit {
+    _global: OwnedSemaphorePermit,
+    _tool: Option<OwnedSemaphorePermit>,
+}
+
+impl Throttle {
+    pub fn from_config(config: &ServerConfig) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(config.max_concurrent_calls.max(1))),
+            per_tool: config
+                .tool_concurrency
+                .iter()
+                .map(|(tool, limit)| (tool.clone(), Arc::new(Semaphore::new((*limit).max(1)))))
+                .collect(),
+            bucket: config
+                .rate_limit
+                .as_ref()
+                .map(|limit| Mutex::new(TokenBucket::new(limit, Instant::now()))),
+        }
+    }
+
+    /// Admit a call to `tool`, waiting for concurrency slots if necessary.
+    ///
+    /// Returns an error message when the rate limit is exhausted.
+    pub async fn acquire(&self, tool: &str) -> Result<ThrottlePermit, String> {
+        if let Some(bucket) = &self.bucket {
+            let mut bucket = bucket.lock().unwrap_or_else(|e| e.into_inner());
+            if let Err(retry_after) = bucket.try_take(Instant::now()) {
+                return Err(format!(
+                    "Rate limit exceeded; retry in {} ms",
+                    retry_after.as_millis().max(1)
+                ));
+            }
+        }
+
+        let tool_permit = match self.per_tool.get(tool) {
+            Some(semaphore) => Some(acquire_owned(semaphore).await?),
+            None => None,
+        };
+        let global_permit = acquire_owned(&self.global).await?;
+
+        Ok(ThrottlePermit {
+            _global: global_permit,
+            _tool: tool_permit,
+        })
+    }
+}
+
+async fn acquire_owned(semaphore: &Arc<Semaphore>) -> Result<OwnedSemaphorePermit, String> {
+    semaphore
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|_| "Server is shutting down".to_string())
+}
+
+/// Classic token bucket: refills continuously at `rate` tokens per second
+/// up to `capacity`.
+struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(config: &RateLimitConfig, now: Instant) -> Self {
+        let capacity = f64::from(config.burst.max(1));
+        Self {
+            capacity,
+            rate: config.calls_per_second.max(f64::MIN_POSITIVE),
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    /// Take one token, or report how long until one is available.
+    fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limit(calls_per_second: f64, burst: u32) -> RateLimitConfig {
+        RateLimitConfig {
+            calls_per_second,
+            burst,
+        }
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_rejects() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(&rate_limit(1.0, 3), start);
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_err());
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(&rate_limit(2.0, 1), start);
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_err());
+        assert!(bucket.try_take(start + Duration::from_millis(600)).is_ok());
+    }
+
+    #[test]
+    fn bucket_reports_retry_delay() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(&rate_limit(4.0, 1), start);
+        bucket.try_take(start).unwrap();
+        let retry = bucket.try_take(start).unwrap_err();
+        assert_eq!(retry, Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn throttle_rejects_when_rate_limited() {
+        let config = ServerConfig {
+            rate_limit: Some(rate_limit(0.001, 1)),
+            ..ServerConfig::default()
+        };
+        let throttle = Throttle::from_config(&config);
+        let first = throttle.acquire("api_search").await;
+        assert!(first.is_ok());
+        let second = throttle.acquire("api_search").await;
+        assert!(second.is_err());
+    }
+
+    #[tokio::test]
+    async fn per_tool_cap_limits_concurrent_permits() {
+        let mut config = ServerConfig::default();
+        config.tool_concurrency.insert("batch_call".to_string(), 1);
+        let throttle = Throttle::from_config(&config);
+
+        let _held = throttle.acquire("batch_call").await.unwrap();
+        assert_eq!(throttle.per_tool["batch_call"].available_permits(), 0);
+        // Other tools are unaffected by the batch_call cap
+        assert!(throttle.acquire("api_search").await.is_ok());
+    }
+}
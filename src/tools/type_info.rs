@@ -1,18 +1,74 @@
-use super::SharedState;
 use crate::parser::index::{FieldKind, ItemKind};
+use crate::toolkit::AmariToolkit;
 use async_trait::async_trait;
 use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
 pub struct TypeInfoHandler {
-    pub state: Arc<SharedState>,
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TypeInfoRequest {
+    pub name: String,
+    pub workspace: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypeInfoResponse<'a> {
+    pub name: &'a str,
+    pub kind: &'static str,
+    pub full_path: &'a str,
+    pub signature: &'a str,
+    pub doc_comment: &'a str,
+    pub generics: Option<&'a str>,
+    pub feature_gate: Option<&'a str>,
+    pub fields: Option<FieldsView<'a>>,
+    pub variants: Option<Vec<VariantView<'a>>>,
+    pub methods: Vec<MethodView<'a>>,
+    pub source_file: String,
+    pub line: usize,
+}
+
+/// Struct or variant fields: named fields, tuple types, or `null` for unit.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum FieldsView<'a> {
+    Named(Vec<NamedField<'a>>),
+    Tuple(&'a [String]),
+    Unit,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NamedField<'a> {
+    pub name: &'a str,
+    #[serde(rename = "type")]
+    pub ty: &'a str,
+    pub doc: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VariantView<'a> {
+    pub name: &'a str,
+    pub fields: FieldsView<'a>,
+    pub doc: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MethodView<'a> {
+    pub name: &'a str,
+    pub signature: &'a str,
+    pub doc_summary: String,
+    pub trait_impl: Option<&'a str>,
 }
 
 #[async_trait]
 impl ToolHandler for TypeInfoHandler {
     fn metadata(&self) -> Option<pmcp::ToolInfo> {
-        Some(super::tool_info(
+        Some(super::library_tool_info(
+            &self.toolkit,
             "type_info",
             "Get full details on a specific type including signature, fields, methods, trait impls, and documentation",
             json!({
@@ -21,7 +77,8 @@ impl ToolHandler for TypeInfoHandler {
                     "name": {
                         "type": "string",
                         "description": "Type name (e.g. 'Multivector' or 'amari::core::Multivector')"
-                    }
+                    },
+                    "workspace": super::workspace_property()
                 },
                 "required": ["name"]
             }),
@@ -29,113 +86,89 @@ impl ToolHandler for TypeInfoHandler {
     }
 
     async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
-        let name = args["name"]
-            .as_str()
-            .ok_or_else(|| McpError::invalid_params("name is required"))?;
-
-        let type_items: Vec<_> = self
-            .state
-            .index
-            .search(name)
-            .into_iter()
-            .filter(|item| item.name == name || item.full_path.ends_with(name))
-            .filter(|item| {
-                matches!(
-                    item.kind,
-                    ItemKind::Struct { .. }
-                        | ItemKind::Enum { .. }
-                        | ItemKind::Trait { .. }
-                        | ItemKind::TypeAlias
-                )
-            })
-            .collect();
-
-        if type_items.is_empty() {
-            return Ok(json!({"error": format!("Type '{name}' not found")}));
-        }
+        let request: TypeInfoRequest = super::parse_args(args)?;
+        let name = request.name.as_str();
+        super::limits::check_query("name", name, &self.toolkit.manifest().server.limits)?;
+        let toolkit = match self.toolkit.workspace(request.workspace.as_deref()).await {
+            Ok(toolkit) => toolkit,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
+        };
 
-        let primary = type_items[0];
+        let details = match toolkit.type_info(name) {
+            Ok(details) => details,
+            Err(e) => return Ok(json!({"error": e.to_string()})),
+        };
+        let primary = details.item;
 
-        let methods: Vec<Value> = self
-            .state
-            .index
-            .search(&primary.name)
+        let methods = details
+            .methods
             .into_iter()
-            .filter(|item| {
-                if let ItemKind::Impl { ref self_type, .. } = item.kind {
-                    self_type.contains(&primary.name)
-                } else {
-                    false
-                }
-            })
-            .map(|item| {
-                let trait_name = if let ItemKind::Impl { ref trait_name, .. } = item.kind {
-                    trait_name.clone()
-                } else {
-                    None
-                };
-                json!({
-                    "name": item.name,
-                    "signature": item.signature,
-                    "doc_summary": crate::parser::display::first_sentence(&item.doc_comment),
-                    "trait_impl": trait_name,
-                })
+            .map(|item| MethodView {
+                name: &item.name,
+                signature: &item.signature,
+                doc_summary: crate::parser::display::first_sentence(&item.doc_comment),
+                trait_impl: match &item.kind {
+                    ItemKind::Impl { trait_name, .. } => trait_name.as_deref(),
+                    _ => None,
+                },
             })
             .collect();
 
-        let fields_json = match &primary.kind {
-            ItemKind::Struct { fields } => Some(render_fields(fields)),
+        let fields = match &primary.kind {
+            ItemKind::Struct { fields } => Some(fields_view(fields)),
             _ => None,
         };
 
-        let variants_json: Option<Vec<Value>> = match &primary.kind {
+        let variants = match &primary.kind {
             ItemKind::Enum { variants } => Some(
                 variants
                     .iter()
-                    .map(|v| {
-                        json!({
-                            "name": v.name,
-                            "fields": render_fields(&v.fields),
-                            "doc": v.doc_comment,
-                        })
+                    .map(|v| VariantView {
+                        name: &v.name,
+                        fields: fields_view(&v.fields),
+                        doc: &v.doc_comment,
                     })
                     .collect(),
             ),
             _ => None,
         };
 
-        Ok(json!({
-            "name": primary.name,
-            "kind": match &primary.kind {
+        super::respond(&TypeInfoResponse {
+            name: &primary.name,
+            kind: match &primary.kind {
                 ItemKind::Struct { .. } => "struct",
                 ItemKind::Enum { .. } => "enum",
                 ItemKind::Trait { .. } => "trait",
                 ItemKind::TypeAlias => "type_alias",
                 _ => "other",
             },
-            "full_path": primary.full_path,
-            "signature": primary.signature,
-            "doc_comment": primary.doc_comment,
-            "generics": primary.generics,
-            "feature_gate": primary.feature_gate,
-            "fields": fields_json,
-            "variants": variants_json,
-            "methods": methods,
-            "source_file": primary.source_file.display().to_string(),
-            "line": primary.line_number,
-        }))
+            full_path: &primary.full_path,
+            signature: &primary.signature,
+            doc_comment: &primary.doc_comment,
+            generics: primary.generics.as_deref(),
+            feature_gate: primary.feature_gate.as_deref(),
+            fields,
+            variants,
+            methods,
+            source_file: primary.source_file.display().to_string(),
+            line: primary.line_number,
+        })
     }
 }
 
-fn render_fields(fields: &FieldKind) -> Value {
+fn fields_view(fields: &FieldKind) -> FieldsView<'_> {
     match fields {
-        FieldKind::Named(fields) => {
-            json!(fields
+        FieldKind::Named(fields) => FieldsView::Named(
+            fields
                 .iter()
-                .map(|f| { json!({"name": f.name, "type": f.ty, "doc": f.doc_comment}) })
-                .collect::<Vec<_>>())
-        }
-        FieldKind::Tuple(types) => json!(types),
-        FieldKind::Unit => json!(null),
+                .map(|f| NamedField {
+                    name: &f.name,
+                    ty: &f.ty,
+                    doc: &f.doc_comment,
+                })
+                .collect(),
+        ),
+        FieldKind::Tuple(types) => FieldsView::Tuple(types),
+        FieldKind::Unit => FieldsView::Unit,
     }
 }
@@ -0,0 +1,131 @@
+use crate::toolkit::{AmariToolkit, UpgradeFinding, UpgradeIssue};
+use async_trait::async_trait;
+use pmcp::{Error as McpError, RequestHandlerExtra, ToolHandler};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+
+pub struct UpgradeAdviceHandler {
+    pub toolkit: Arc<AmariToolkit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpgradeAdviceRequest {
+    pub path: String,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpgradeAdviceResponse<'a> {
+    pub project: &'a str,
+    pub library_version: &'a str,
+    pub files_scanned: usize,
+    pub usages_checked: usize,
+    /// Finding counts before `limit` truncation.
+    pub summary: AdviceSummary,
+    pub findings: Vec<Finding<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unparsed_files: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdviceSummary {
+    pub deprecated: usize,
+    pub missing: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Finding<'a> {
+    pub path: String,
+    pub file: String,
+    pub line: usize,
+    #[serde(flatten)]
+    pub issue: FindingIssue<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum FindingIssue<'a> {
+    Deprecated {
+        item: &'a str,
+        note: &'a str,
+    },
+    Missing {
+        /// Full paths of indexed items with similar names.
+        suggestions: Vec<&'a str>,
+    },
+}
+
+#[async_trait]
+impl ToolHandler for UpgradeAdviceHandler {
+    fn metadata(&self) -> Option<pmcp::ToolInfo> {
+        Some(super::library_tool_info(
+            &self.toolkit,
+            "upgrade_advice",
+            "Scan a Rust project for uses of the library and list deprecated or missing items, with similarly named replacements",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Project directory; must be under one of the manifest's [server] project_roots"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum findings returned (default 50, capped by server limits)"
+                    }
+                },
+                "required": ["path"]
+            }),
+        ))
+    }
+
+    async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, McpError> {
+        let toolkit = self.toolkit.current();
+        let request: UpgradeAdviceRequest = super::parse_args(args)?;
+        let limits = &toolkit.manifest().server.limits;
+        let limit = super::limits::result_limit(request.limit, 50, limits)?;
+
+        let report = match toolkit.upgrade_advice(Path::new(&request.path)) {
+            Ok(report) => report,
+            Err(e) => return Ok(json!({ "error": e.to_string() })),
+        };
+
+        let deprecated = report
+            .findings
+            .iter()
+            .filter(|f| matches!(f.issue, UpgradeIssue::Deprecated { .. }))
+            .count();
+        super::respond(&UpgradeAdviceResponse {
+            project: &request.path,
+            library_version: &toolkit.manifest().library.version,
+            files_scanned: report.files_scanned,
+            usages_checked: report.usages_checked,
+            summary: AdviceSummary {
+                deprecated,
+                missing: report.findings.len() - deprecated,
+            },
+            findings: report.findings.iter().take(limit).map(finding).collect(),
+            unparsed_files: report.errors,
+        })
+    }
+}
+
+fn finding<'a>(found: &UpgradeFinding<'a>) -> Finding<'a> {
+    let issue = match &found.issue {
+        &UpgradeIssue::Deprecated { item } => FindingIssue::Deprecated {
+            item: &item.full_path,
+            note: item.deprecated.as_deref().unwrap_or_default(),
+        },
+        UpgradeIssue::Missing { suggestions } => FindingIssue::Missing {
+            suggestions: suggestions.iter().map(|&i| i.full_path.as_str()).collect(),
+        },
+    };
+    Finding {
+        path: found.usage.display_path(),
+        file: found.usage.file.display().to_string(),
+        line: found.usage.line,
+        issue,
+    }
+}
@@ -0,0 +1,286 @@
+use super::registry::ToolRegistry;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Distinct argument shapes or failure reasons kept per tool; further
+/// distinct values are counted under `<other>`.
+pub const MAX_DISTINCT: usize = 20;
+
+/// Longest failure reason kept, in characters.
+const MAX_REASON_LEN: usize = 120;
+
+/// In-memory per-tool call statistics, reset when the server restarts.
+#[derive(Default)]
+pub struct UsageStats {
+    tools: Mutex<HashMap<String, ToolUsage>>,
+}
+
+#[derive(Default, Clone)]
+struct ToolUsage {
+    calls: u64,
+    failures: u64,
+    rejected: u64,
+    invalid: u64,
+    total_ms: u64,
+    max_ms: u64,
+    shapes: HashMap<String, u64>,
+    failure_reasons: HashMap<String, u64>,
+}
+
+/// How a dispatched call ended, as seen by the statistics.
+pub enum CallOutcome<'a> {
+    Succeeded,
+    /// The handler returned an error, or a response with an `error` field.
+    Failed(&'a str),
+    /// The throttle turned the call away before it ran.
+    Rejected(&'a str),
+    /// The arguments failed validation before the handler ran.
+    Invalid(&'a str),
+}
+
+impl UsageStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, tool: &str, shape: String, elapsed_ms: u64, outcome: CallOutcome<'_>) {
+        let mut tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        let usage = tools.entry(tool.to_string()).or_default();
+        usage.calls += 1;
+        usage.total_ms += elapsed_ms;
+        usage.max_ms = usage.max_ms.max(elapsed_ms);
+        bump(&mut usage.shapes, shape);
+        match outcome {
+            CallOutcome::Succeeded => {}
+            CallOutcome::Failed(reason) => {
+                usage.failures += 1;
+                bump(&mut usage.failure_reasons, truncate(reason));
+            }
+            CallOutcome::Rejected(reason) => {
+                usage.rejected += 1;
+                bump(&mut usage.failure_reasons, truncate(reason));
+            }
+            CallOutcome::Invalid(reason) => {
+                usage.invalid += 1;
+                bump(&mut usage.failure_reasons, truncate(reason));
+            }
+        }
+    }
+
+    /// Statistics for every tool, or only `tool` when given.
+    pub fn snapshot(&self, tool: Option<&str>) -> Value {
+        let tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<&String> = tools
+            .keys()
+            .filter(|name| tool.is_none_or(|t| t == name.as_str()))
+            .collect();
+        names.sort();
+
+        let entries: Map<String, Value> = names
+            .into_iter()
+            .map(|name| {
+                let usage = &tools[name];
+                // Latency covers only calls that reached the handler.
+                let ran = usage.calls - usage.rejected - usage.invalid;
+                (
+                    name.clone(),
+                    json!({
+                        "calls": usage.calls,
+                        "failures": usage.failures,
+                        "rejected": usage.rejected,
+                        "invalid": usage.invalid,
+                        "mean_ms": usage.total_ms.checked_div(ran).unwrap_or(0),
+                        "max_ms": usage.max_ms,
+                        "argument_shapes": most_frequent(&usage.shapes),
+                        "failure_reasons": most_frequent(&usage.failure_reasons),
+                    }),
+                )
+            })
+            .collect();
+        Value::Object(entries)
+    }
+
+    /// One-line summary for periodic logging, busiest tools first.
+    pub fn summary(&self) -> String {
+        let tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries: Vec<(&String, &ToolUsage)> = tools.iter().collect();
+        entries.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then(a.0.cmp(b.0)));
+        let total: u64 = entries.iter().map(|(_, usage)| usage.calls).sum();
+        let parts: Vec<String> = entries
+            .iter()
+            .map(|(name, usage)| {
+                format!(
+                    "{name}={} ({} failed)",
+                    usage.calls,
+                    usage.failures + usage.rejected + usage.invalid
+                )
+            })
+            .collect();
+        format!("{total} tool call(s): {}", parts.join(", "))
+    }
+}
+
+fn bump(counts: &mut HashMap<String, u64>, key: String) {
+    let key = if counts.len() >= MAX_DISTINCT && !counts.contains_key(&key) {
+        "<other>".to_string()
+    } else {
+        key
+    };
+    *counts.entry(key).or_default() += 1;
+}
+
+fn truncate(reason: &str) -> String {
+    reason.chars().take(MAX_REASON_LEN).collect()
+}
+
+fn most_frequent(counts: &HashMap<String, u64>) -> Vec<Value> {
+    let mut entries: Vec<(&String, &u64)> = counts.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    entries
+        .into_iter()
+        .map(|(value, count)| json!({"value": value, "count": count}))
+        .collect()
+}
+
+/// Describe the structure of tool arguments without their values, e.g.
+/// `{crate: string, limit: integer}`.
+pub fn argument_shape(args: &Value) -> String {
+    match args {
+        Value::Object(map) => {
+            let mut fields: Vec<String> = map
+                .iter()
+                .map(|(key, value)| format!("{key}: {}", type_name(value)))
+                .collect();
+            fields.sort();
+            format!("{{{}}}", fields.join(", "))
+        }
+        other => type_name(other).to_string(),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsageStatsRequest {
+    pub tool: Option<String>,
+}
+
+/// Register the `usage_stats` tool, reporting from `stats`.
+pub fn register_usage_stats(tools: &mut ToolRegistry, stats: Arc<UsageStats>) {
+    tools.register_fn(
+        "usage_stats",
+        "Report per-tool call counts, latencies, argument shapes, and failure reasons since the server started",
+        json!({
+            "type": "object",
+            "properties": {
+                "tool": {
+                    "type": "string",
+                    "description": "Only report this tool"
+                }
+            }
+        }),
+        move |request: UsageStatsRequest, _extra| {
+            let snapshot = stats.snapshot(request.tool.as_deref());
+            async move { Ok(json!({ "tools": snapshot })) }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argument_shape_ignores_values_and_key_order() {
+        assert_eq!(
+            argument_shape(&json!({"limit": 5, "crate": "core"})),
+            "{crate: string, limit: integer}"
+        );
+        assert_eq!(
+            argument_shape(&json!({"crate": "gpu", "limit": 100})),
+            "{crate: string, limit: integer}"
+        );
+        assert_eq!(argument_shape(&json!({})), "{}");
+    }
+
+    #[test]
+    fn records_calls_failures_and_rejections() {
+        let stats = UsageStats::new();
+        stats.record("api_search", "{}".into(), 4, CallOutcome::Succeeded);
+        stats.record(
+            "api_search",
+            "{}".into(),
+            8,
+            CallOutcome::Failed("bad query"),
+        );
+        stats.record(
+            "api_search",
+            "{}".into(),
+            0,
+            CallOutcome::Rejected("rate limited"),
+        );
+        stats.record(
+            "api_search",
+            "{}".into(),
+            0,
+            CallOutcome::Invalid("/query: required string is missing"),
+        );
+
+        let snapshot = stats.snapshot(None);
+        let usage = &snapshot["api_search"];
+        assert_eq!(usage["calls"], 4);
+        assert_eq!(usage["failures"], 1);
+        assert_eq!(usage["rejected"], 1);
+        assert_eq!(usage["invalid"], 1);
+        assert_eq!(usage["mean_ms"], 6);
+        assert_eq!(usage["max_ms"], 8);
+        assert_eq!(usage["argument_shapes"][0]["count"], 4);
+        assert_eq!(usage["failure_reasons"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn snapshot_filters_by_tool() {
+        let stats = UsageStats::new();
+        stats.record("api_search", "{}".into(), 1, CallOutcome::Succeeded);
+        stats.record("type_info", "{}".into(), 1, CallOutcome::Succeeded);
+        let snapshot = stats.snapshot(Some("type_info"));
+        assert!(snapshot.get("api_search").is_none());
+        assert_eq!(snapshot["type_info"]["calls"], 1);
+    }
+
+    #[test]
+    fn distinct_values_are_bounded() {
+        let stats = UsageStats::new();
+        for i in 0..MAX_DISTINCT + 5 {
+            stats.record("t", format!("shape{i}"), 0, CallOutcome::Succeeded);
+        }
+        let shapes = stats.snapshot(None)["t"]["argument_shapes"].clone();
+        assert_eq!(shapes.as_array().unwrap().len(), MAX_DISTINCT + 1);
+        assert_eq!(shapes[0]["value"], "<other>");
+        assert_eq!(shapes[0]["count"], 5);
+    }
+
+    #[test]
+    fn summary_lists_busiest_tools_first() {
+        let stats = UsageStats::new();
+        stats.record("a", "{}".into(), 0, CallOutcome::Succeeded);
+        stats.record("b", "{}".into(), 0, CallOutcome::Succeeded);
+        stats.record("b", "{}".into(), 0, CallOutcome::Failed("x"));
+        assert_eq!(
+            stats.summary(),
+            "3 tool call(s): b=2 (1 failed), a=1 (0 failed)"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Schema validation of tool arguments with errors clients can act on:
+//! each problem names the JSON pointer of the field, the expected type,
+//! and the error closes with an example of a valid call.
+
+use serde_json::{json, Map, Value};
+
+/// A single argument that doesn't match the tool's input schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub pointer: String,
+    pub expected: String,
+    /// What was supplied, or `None` when the field is missing.
+    pub found: Option<String>,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.found {
+            Some(found) => write!(
+                f,
+                "{}: expected {}, found {found}",
+                self.pointer, self.expected
+            ),
+            None => write!(f, "{}: required {} is missing", self.pointer, self.expected),
+        }
+    }
+}
+
+/// Check `args` against the subset of JSON Schema used by tool schemas:
+/// `type`, `properties`, `required`, `items`, and `enum`.
+pub fn validate_args(args: &Value, schema: &Value) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    validate_value(args, schema, "", &mut violations);
+    violations
+}
+
+fn validate_value(value: &Value, schema: &Value, pointer: &str, violations: &mut Vec<Violation>) {
+    if let Some(expected) = schema.get("type").and_then(|t| t.as_str()) {
+        if !has_type(value, expected) {
+            violations.push(Violation {
+                pointer: display_pointer(pointer),
+                expected: expected.to_string(),
+                found: Some(describe(value)),
+            });
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !options.contains(value) {
+            let names: Vec<String> = options.iter().map(Value::to_string).collect();
+            violations.push(Violation {
+                pointer: display_pointer(pointer),
+                expected: format!("one of {}", names.join(", ")),
+                found: Some(value.to_string()),
+            });
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            for key in required(schema) {
+                if map.get(key).is_none_or(Value::is_null) {
+                    let expected = properties
+                        .and_then(|p| p.get(key))
+                        .and_then(|s| s.get("type"))
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("value");
+                    violations.push(Violation {
+                        pointer: child_pointer(pointer, key),
+                        expected: expected.to_string(),
+                        found: None,
+                    });
+                }
+            }
+            if let Some(properties) = properties {
+                for (key, field) in map {
+                    if let (Some(field_schema), false) = (properties.get(key), field.is_null()) {
+                        validate_value(
+                            field,
+                            field_schema,
+                            &child_pointer(pointer, key),
+                            violations,
+                        );
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(item, item_schema, &format!("{pointer}/{index}"), violations);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `pointer` extended by the object key `key`, escaped per RFC 6901 so keys
+/// containing `~` or `/` stay unambiguous.
+pub fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn required(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default()
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Array(_) => "array".to_string(),
+        Value::Object(_) => "object".to_string(),
+        scalar => scalar.to_string(),
+    }
+}
+
+fn display_pointer(pointer: &str) -> String {
+    if pointer.is_empty() {
+        "/".to_string()
+    } else {
+        pointer.to_string()
+    }
+}
+
+/// A minimal valid call for the tool: every required field filled with a
+/// placeholder of the right type (the first option for enums).
+pub fn example_arguments(schema: &Value) -> Value {
+    let properties = schema.get("properties").and_then(|p| p.as_object());
+    let mut example = Map::new();
+    for key in required(schema) {
+        let field = properties.and_then(|p| p.get(key)).unwrap_or(&Value::Null);
+        example.insert(key.to_string(), example_value(key, field));
+    }
+    Value::Object(example)
+}
+
+fn example_value(key: &str, schema: &Value) -> Value {
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(|e| e.as_array())
+        .and_then(|e| e.first())
+    {
+        return first.clone();
+    }
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("integer") | Some("number") => json!(10),
+        Some("boolean") => json!(true),
+        Some("array") => json!([]),
+        Some("object") => example_arguments(schema),
+        _ => json!(format!("<{key}>")),
+    }
+}
+
+/// Error message for a call whose arguments failed validation.
+pub fn error_message(tool: &str, violations: &[Violation], schema: &Value) -> String {
+    let mut message = format!("Invalid arguments for {tool}:");
+    for violation in violations {
+        message.push_str(&format!("\n  {violation}"));
+    }
+    message.push_str(&format!(
+        "\nExample: {}",
+        json!({"tool": tool, "arguments": example_arguments(schema)})
+    ));
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string"},
+                "kind": {"type": "string", "enum": ["struct", "trait"]},
+                "limit": {"type": "integer"},
+                "calls": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {"tool": {"type": "string"}},
+                        "required": ["tool"]
+                    }
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    #[test]
+    fn valid_arguments_have_no_violations() {
+        let args = json!({"query": "Rotor", "kind": "trait", "limit": 3});
+        assert!(validate_args(&args, &schema()).is_empty());
+    }
+
+    #[test]
+    fn reports_missing_required_fields() {
+        let violations = validate_args(&json!({"limit": 3}), &schema());
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "/query: required string is missing"
+        );
+    }
+
+    #[test]
+    fn reports_type_and_enum_mismatches_by_pointer() {
+        let args = json!({"query": "x", "kind": "module", "limit": 2.5});
+        let messages: Vec<String> = validate_args(&args, &schema())
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert!(messages.contains(&"/limit: expected integer, found 2.5".to_string()));
+        assert!(messages.contains(
+            &"/kind: expected one of \"struct\", \"trait\", found \"module\"".to_string()
+        ));
+    }
+
+    #[test]
+    fn descends_into_array_items() {
+        let args = json!({"query": "x", "calls": [{"tool": "a"}, {}]});
+        let violations = validate_args(&args, &schema());
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].pointer, "/calls/1/tool");
+    }
+
+    #[test]
+    fn escapes_keys_in_pointers() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"a/b~c": {"type": "integer"}},
+            "required": ["x/y"]
+        });
+        let args = json!({"a/b~c": "one"});
+        let pointers: Vec<String> = validate_args(&args, &schema)
+            .into_iter()
+            .map(|v| v.pointer)
+            .collect();
+        assert_eq!(pointers, ["/x~1y", "/a~1b~0c"]);
+    }
+
+    #[test]
+    fn example_fills_required_fields() {
+        assert_eq!(example_arguments(&schema()), json!({"query": "<query>"}));
+        let message = error_message(
+            "api_search",
+            &validate_args(&json!({}), &schema()),
+            &schema(),
+        );
+        assert!(message.starts_with("Invalid arguments for api_search:\n  /query"));
+        // Key order depends on whether serde_json preserves insertion order.
+        let example = message.split_once("\nExample: ").unwrap().1;
+        assert_eq!(
+            serde_json::from_str::<Value>(example).unwrap(),
+            json!({"tool": "api_search", "arguments": {"query": "<query>"}})
+        );
+    }
+}
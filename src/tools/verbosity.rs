@@ -0,0 +1,231 @@
+//! The `verbosity` argument every tool accepts: `minimal` trims responses
+//! for programmatic consumers, `explain` adds a glossary of the fields
+//! present for readers new to the output, and `normal` (the default)
+//! returns the tool's response unchanged.
+
+use serde_json::{json, Map, Value};
+
+/// Argument name reserved for verbosity on every tool.
+pub const ARGUMENT: &str = "verbosity";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    Minimal,
+    #[default]
+    Normal,
+    Explain,
+}
+
+/// Fields dropped from `minimal` responses: prose and file paths. `line` is
+/// kept so consumers can still locate an item.
+const MINIMAL_DROP: &[&str] = &["doc_summary", "source_file", "module_docs"];
+
+/// Descriptions attached to `explain` responses, for each field present.
+const GLOSSARY: &[(&str, &str)] = &[
+    (
+        "full_path",
+        "Path to the item through its defining crate and modules",
+    ),
+    (
+        "signature",
+        "Declaration as written in the source, with bodies elided",
+    ),
+    (
+        "kind",
+        "Item category: function, struct, enum, trait, type, const, impl, or reexport",
+    ),
+    (
+        "feature_gate",
+        "Cargo feature that must be enabled to use the item; null when always available",
+    ),
+    (
+        "generics",
+        "Generic parameters and where-clauses of the item",
+    ),
+    ("doc_summary", "First sentence of the item's doc comment"),
+    ("doc_comment", "Full doc comment of the item"),
+    ("documentation", "Doc comment text, verbatim"),
+    ("module_docs", "Inner (`//!`) documentation of the module"),
+    ("source_file", "File the item is declared in"),
+    ("line", "Line of the declaration in `source_file`"),
+    (
+        "alias",
+        "Short crate name used by the umbrella crate's re-exports",
+    ),
+    (
+        "trait_impl",
+        "Trait implemented by this impl block; null for inherent methods",
+    ),
+    (
+        "methods",
+        "Functions from impl blocks whose self type is this item",
+    ),
+    ("depends_on", "Workspace crates this crate depends on"),
+    ("depended_by", "Workspace crates that depend on this crate"),
+    ("total_matches", "Matches before `limit` was applied"),
+    (
+        "added",
+        "Items present in the current index but not the baseline",
+    ),
+    (
+        "removed",
+        "Items present in the baseline but not the current index",
+    ),
+    (
+        "changed",
+        "Items whose signature differs between the baseline and the current index",
+    ),
+    (
+        "warnings",
+        "Adjustments made to the arguments before the call ran",
+    ),
+];
+
+impl Verbosity {
+    /// Remove the `verbosity` argument from `args` and parse it.
+    pub fn take(args: &mut Value) -> Result<Self, String> {
+        let Some(value) = args.as_object_mut().and_then(|map| map.remove(ARGUMENT)) else {
+            return Ok(Self::default());
+        };
+        match value.as_str() {
+            Some("minimal") => Ok(Self::Minimal),
+            Some("normal") => Ok(Self::Normal),
+            Some("explain") => Ok(Self::Explain),
+            _ => Err(format!(
+                "verbosity must be 'minimal', 'normal', or 'explain', got {value}"
+            )),
+        }
+    }
+
+    /// Reshape a tool response for this verbosity.
+    pub fn apply(self, response: &mut Value) {
+        match self {
+            Verbosity::Normal => {}
+            Verbosity::Minimal => strip(response),
+            Verbosity::Explain => {
+                let mut present = Vec::new();
+                collect_keys(response, &mut present);
+                let explanation: Map<String, Value> = GLOSSARY
+                    .iter()
+                    .filter(|(key, _)| present.contains(key))
+                    .map(|(key, text)| (key.to_string(), json!(text)))
+                    .collect();
+                if let (Value::Object(map), false) = (&mut *response, explanation.is_empty()) {
+                    map.insert("explanation".to_string(), Value::Object(explanation));
+                }
+            }
+        }
+    }
+}
+
+/// The schema property advertising the argument, added to every tool.
+pub fn schema_property() -> Value {
+    json!({
+        "type": "string",
+        "description": "minimal drops prose and file paths; explain adds a glossary of the response fields (default normal)",
+        "enum": ["minimal", "normal", "explain"]
+    })
+}
+
+fn strip(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, field| !MINIMAL_DROP.contains(&key.as_str()) && !is_empty(field));
+            for field in map.values_mut() {
+                strip(field);
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                strip(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+fn collect_keys<'a>(value: &'a Value, keys: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map {
+                if !keys.contains(&key.as_str()) {
+                    keys.push(key);
+                }
+                collect_keys(field, keys);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_keys(item, keys);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_removes_and_parses_the_argument() {
+        let mut args = json!({"query": "Rotor", "verbosity": "minimal"});
+        assert_eq!(Verbosity::take(&mut args), Ok(Verbosity::Minimal));
+        assert_eq!(args, json!({"query": "Rotor"}));
+
+        let mut args = json!({"query": "Rotor"});
+        assert_eq!(Verbosity::take(&mut args), Ok(Verbosity::Normal));
+        assert!(Verbosity::take(&mut json!({"verbosity": "loud"})).is_err());
+    }
+
+    #[test]
+    fn minimal_drops_prose_paths_and_empty_fields() {
+        let mut response = json!({
+            "results": [{
+                "name": "Rotor",
+                "signature": "pub struct Rotor",
+                "doc_summary": "A rotor.",
+                "source_file": "/src/rotor.rs",
+                "line": 10,
+                "feature_gate": null,
+                "methods": []
+            }],
+            "total_matches": 1
+        });
+        Verbosity::Minimal.apply(&mut response);
+        assert_eq!(
+            response,
+            json!({
+                "results": [{"name": "Rotor", "signature": "pub struct Rotor", "line": 10}],
+                "total_matches": 1
+            })
+        );
+    }
+
+    #[test]
+    fn explain_describes_fields_present() {
+        let mut response = json!({"results": [{"signature": "fn f()", "feature_gate": null}]});
+        Verbosity::Explain.apply(&mut response);
+        let explanation = response["explanation"].as_object().unwrap();
+        assert!(explanation.contains_key("signature"));
+        assert!(explanation.contains_key("feature_gate"));
+        assert!(!explanation.contains_key("depends_on"));
+    }
+
+    #[test]
+    fn normal_leaves_response_unchanged() {
+        let mut response = json!({"doc_summary": "kept", "x": null});
+        let original = response.clone();
+        Verbosity::Normal.apply(&mut response);
+        assert_eq!(response, original);
+    }
+}
@@ -0,0 +1,311 @@
+//! Polling watch over the indexed source files.
+//!
+//! The index is built once at startup, so edits to the checkout (a `git
+//! pull`, a branch switch) would leave tool output stale. With
+//! `[server] watch_interval_secs` set, the server periodically compares each
+//! source file's size and modification time against the previous pass. When
+//! any changed, it re-indexes the library off the async workers and swaps the
+//! new index in with [`SharedState::reindex`]; the reference tools pick it up
+//! on their next call, with fresh doc-search, workspace and baseline caches.
+//! Each pass's [`SourceChange`]s also go to the server, which sends
+//! `notifications/resources/updated` for the URIs the client subscribed to
+//! and `notifications/resources/list_changed` when files were added or
+//! removed. If the rebuild fails, the previous index keeps serving and
+//! `server_info` reports it stale.
+
+use crate::resources::SourceResources;
+use crate::tools::SharedState;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Size and modification time of each watched file, keyed by URI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    files: BTreeMap<String, (u64, Option<SystemTime>)>,
+}
+
+impl Snapshot {
+    /// Stat each `(uri, path)`; files that can't be read are left out, so
+    /// they show up as removed.
+    pub fn take(files: impl IntoIterator<Item = (String, PathBuf)>) -> Self {
+        let files = files
+            .into_iter()
+            .filter_map(|(uri, path)| {
+                let meta = std::fs::metadata(path).ok()?;
+                Some((uri, (meta.len(), meta.modified().ok())))
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// URIs added, removed, or modified in `newer`, sorted.
+    pub fn changes(&self, newer: &Snapshot) -> Vec<String> {
+        let removed = self
+            .files
+            .keys()
+            .filter(|uri| !newer.files.contains_key(*uri));
+        let added_or_modified = newer
+            .files
+            .iter()
+            .filter(|(uri, stat)| self.files.get(*uri) != Some(stat))
+            .map(|(uri, _)| uri);
+        let changed: BTreeSet<&String> = removed.chain(added_or_modified).collect();
+        changed.into_iter().cloned().collect()
+    }
+
+    /// Whether `newer` has files added or removed, not just modified.
+    pub fn listing_changed(&self, newer: &Snapshot) -> bool {
+        !self.files.keys().eq(newer.files.keys())
+    }
+}
+
+/// A change a watch pass found, for the server to pass on to the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceChange {
+    /// The file at this resource URI was added, removed, or modified.
+    Updated(String),
+    /// Files were added or removed, so `resources/list` is different.
+    ListChanged,
+}
+
+/// Source files changed since the served index was built.
+#[derive(Default)]
+pub struct WatchStatus {
+    changed: Mutex<BTreeSet<String>>,
+    reindexes: AtomicUsize,
+}
+
+impl WatchStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, uris: impl IntoIterator<Item = String>) {
+        self.lock().extend(uris);
+    }
+
+    /// Whether any indexed file changed, so tool output may be out of date.
+    pub fn is_stale(&self) -> bool {
+        !self.lock().is_empty()
+    }
+
+    /// Changed URIs, sorted.
+    pub fn changed(&self) -> Vec<String> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Record a successful re-index, which covers every change so far.
+    pub fn reindexed(&self) {
+        self.lock().clear();
+        self.reindexes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of times the source has been re-indexed since startup.
+    pub fn reindexes(&self) -> usize {
+        self.reindexes.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeSet<String>> {
+        self.changed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Poll the source files of `state`'s library every `interval`. After a
+/// pass that finds changes, re-index the library into `state` and send what
+/// changed to `changes`.
+pub fn spawn(
+    state: Arc<SharedState>,
+    interval: Duration,
+    changes: mpsc::UnboundedSender<SourceChange>,
+) -> tokio::task::JoinHandle<()> {
+    let resources = Arc::new(SourceResources::new(&state));
+    tokio::spawn(async move {
+        let take = |resources: Arc<SourceResources>| {
+            tokio::task::spawn_blocking(move || Snapshot::take(resources.files()))
+        };
+        let Ok(mut previous) = take(resources.clone()).await else {
+            return;
+        };
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let Ok(current) = take(resources.clone()).await else {
+                return;
+            };
+            let changed = previous.changes(&current);
+            let listing_changed = previous.listing_changed(&current);
+            previous = current;
+            if changed.is_empty() {
+                continue;
+            }
+
+            state.watch.record(changed.iter().cloned());
+            match reindex(&state).await {
+                Ok(()) => {
+                    state.watch.reindexed();
+                    info!(
+                        "Re-indexed after {} source file(s) changed (first: {})",
+                        changed.len(),
+                        changed[0]
+                    );
+                }
+                Err(e) => warn!("Re-indexing failed, serving the previous index: {e:#}"),
+            }
+            let list_changed = listing_changed.then_some(SourceChange::ListChanged);
+            let updates = changed.into_iter().map(SourceChange::Updated);
+            for change in list_changed.into_iter().chain(updates) {
+                if changes.send(change).is_err() {
+                    return;
+                }
+            }
+        }
+    })
+}
+
+/// Rebuild the library's index from its manifest and swap it into `state`.
+async fn reindex(state: &Arc<SharedState>) -> anyhow::Result<()> {
+    let building = state.clone();
+    let index = tokio::task::spawn_blocking(move || {
+        let manifest = &building.manifest;
+        anyhow::Ok(crate::parser::build_index(manifest, &manifest.manifest_path)?.validate()?)
+    })
+    .await??;
+    state.reindex(index);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::toolkit::{AmariToolkit, SearchFilter};
+    use std::path::Path;
+
+    fn files(dir: &std::path::Path) -> Vec<(String, PathBuf)> {
+        ["lib.rs", "shapes.rs"]
+            .iter()
+            .map(|name| (format!("lib://source/core/src/{name}"), dir.join(name)))
+            .collect()
+    }
+
+    #[test]
+    fn reports_added_removed_and_modified_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "pub mod shapes;\n").unwrap();
+        let before = Snapshot::take(files(dir.path()));
+        assert!(before
+            .changes(&Snapshot::take(files(dir.path())))
+            .is_empty());
+
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "pub mod shapes;\npub mod more;\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("shapes.rs"), "pub struct Point;\n").unwrap();
+        let after = Snapshot::take(files(dir.path()));
+        assert_eq!(
+            before.changes(&after),
+            vec![
+                "lib://source/core/src/lib.rs",
+                "lib://source/core/src/shapes.rs"
+            ]
+        );
+
+        assert!(before.listing_changed(&after));
+
+        std::fs::remove_file(dir.path().join("shapes.rs")).unwrap();
+        let removed = Snapshot::take(files(dir.path()));
+        assert_eq!(
+            after.changes(&removed),
+            vec!["lib://source/core/src/shapes.rs"]
+        );
+        assert!(after.listing_changed(&removed));
+
+        std::fs::write(dir.path().join("lib.rs"), "pub mod edited;\n").unwrap();
+        let edited = Snapshot::take(files(dir.path()));
+        assert!(!removed.listing_changed(&edited));
+    }
+
+    #[test]
+    fn status_accumulates_changes_until_reindexed() {
+        let status = WatchStatus::new();
+        assert!(!status.is_stale());
+        status.record(["b".to_string(), "a".to_string()]);
+        status.record(["a".to_string()]);
+        assert!(status.is_stale());
+        assert_eq!(status.changed(), vec!["a", "b"]);
+
+        status.reindexed();
+        assert!(!status.is_stale());
+        assert_eq!(status.reindexes(), 1);
+    }
+
+    async fn next(changes: &mut mpsc::UnboundedReceiver<SourceChange>) -> SourceChange {
+        tokio::time::timeout(Duration::from_secs(10), changes.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reindexes_and_reports_changed_files() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let dir = tempfile::tempdir().unwrap();
+        for entry in walkdir::WalkDir::new(fixtures.join("minilib")) {
+            let entry = entry.unwrap();
+            let target = dir
+                .path()
+                .join(entry.path().strip_prefix(&fixtures).unwrap());
+            if entry.file_type().is_dir() {
+                std::fs::create_dir_all(target).unwrap();
+            } else {
+                std::fs::copy(entry.path(), target).unwrap();
+            }
+        }
+        let manifest_path = dir.path().join("minilib.toml");
+        std::fs::copy(fixtures.join("minilib.toml"), &manifest_path).unwrap();
+        let toolkit = Arc::new(AmariToolkit::load(&manifest_path).unwrap());
+        let filter = SearchFilter {
+            kind: None,
+            crate_name: None,
+            limit: None,
+        };
+        assert!(toolkit.search("rescale", &filter).is_empty());
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let watch = spawn(toolkit.state().clone(), Duration::from_millis(20), tx);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let shapes = dir.path().join("minilib/minilib-core/src/shapes.rs");
+        let mut source = std::fs::read_to_string(&shapes).unwrap();
+        source.push_str(
+            "\n/// Scale a length.\npub fn rescale(length: f64) -> f64 {\n    length * 2.0\n}\n",
+        );
+        std::fs::write(&shapes, source).unwrap();
+
+        assert_eq!(
+            next(&mut rx).await,
+            SourceChange::Updated("minilib://source/minilib-core/src/shapes.rs".to_string())
+        );
+        assert_eq!(toolkit.state().watch.reindexes(), 1);
+        assert!(!toolkit.state().watch.is_stale());
+        assert_eq!(toolkit.current().search("rescale", &filter).len(), 1);
+
+        std::fs::write(
+            dir.path().join("minilib/minilib-core/src/scale.rs"),
+            "//! Scaling.\n",
+        )
+        .unwrap();
+        assert_eq!(next(&mut rx).await, SourceChange::ListChanged);
+        assert_eq!(
+            next(&mut rx).await,
+            SourceChange::Updated("minilib://source/minilib-core/src/scale.rs".to_string())
+        );
+        watch.abort();
+    }
+}
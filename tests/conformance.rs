@@ -0,0 +1,99 @@
+//! Golden-output conformance tests.
+//!
+//! Every case in `tests/fixtures/conformance/cases.json` calls a tool against
+//! the `minilib` fixture and compares the normalized response with
+//! `tests/fixtures/conformance/golden/<name>.json`. A case without a golden
+//! file fails; run with `UPDATE_GOLDEN=1` to write missing golden files and
+//! rewrite the rest after an intended output change, then review and commit
+//! them.
+
+use amari_mcp::conformance::{compare, normalize, FLOAT_TOLERANCE};
+use amari_mcp::tools::{build_tools, registry::ToolRegistry, SharedState};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize)]
+struct Case {
+    name: String,
+    tool: String,
+    arguments: Value,
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn load_cases() -> Vec<Case> {
+    let path = fixtures_dir().join("conformance/cases.json");
+    let content = std::fs::read_to_string(&path).expect("Failed to read cases.json");
+    serde_json::from_str(&content).expect("Failed to parse cases.json")
+}
+
+fn fixture_tools() -> ToolRegistry {
+    let manifest_path = fixtures_dir().join("minilib.toml");
+    let manifest = amari_mcp::config::LibraryManifest::load(&manifest_path)
+        .expect("Failed to load fixture manifest");
+    let index = amari_mcp::parser::build_index(&manifest, &manifest_path)
+        .expect("Failed to build fixture index")
+        .validate()
+        .expect("Fixture index failed validation");
+    build_tools(&SharedState::new(index, manifest))
+}
+
+#[tokio::test]
+async fn tool_outputs_match_golden_files() {
+    let tools = fixture_tools();
+    let root = std::fs::canonicalize(fixtures_dir()).unwrap();
+    let golden_dir = fixtures_dir().join("conformance/golden");
+    let update = std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1");
+
+    let mut failures = Vec::new();
+    // Cases run in file order: record_session reports the calls before it.
+    for case in load_cases() {
+        let mut response = tools
+            .call_local(&case.tool, case.arguments)
+            .await
+            .unwrap_or_else(|e| panic!("{}: {} failed: {e}", case.name, case.tool));
+        normalize(&mut response, &root);
+
+        let golden_path = golden_dir.join(format!("{}.json", case.name));
+        if update {
+            let rendered = serde_json::to_string_pretty(&response).unwrap() + "\n";
+            std::fs::write(&golden_path, rendered).unwrap();
+            eprintln!("Wrote {}", golden_path.display());
+            continue;
+        }
+        if !golden_path.exists() {
+            failures.push(format!("{}: no golden file", case.name));
+            continue;
+        }
+
+        let expected: Value = serde_json::from_str(&std::fs::read_to_string(&golden_path).unwrap())
+            .unwrap_or_else(|e| panic!("{}: invalid golden file: {e}", case.name));
+        for diff in compare(&expected, &response, FLOAT_TOLERANCE) {
+            failures.push(format!("{}: {diff}", case.name));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Responses differ from golden files (rerun with UPDATE_GOLDEN=1 if intended):\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn every_registered_tool_has_a_case() {
+    let tools = fixture_tools();
+    let cases = load_cases();
+    let missing: Vec<&str> = tools
+        .names()
+        .into_iter()
+        .filter(|name| !cases.iter().any(|case| case.tool == *name))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "Tools without conformance cases: {missing:?}"
+    );
+}
@@ -0,0 +1,12 @@
+//! Core types for the minilib fixture.
+
+/// A point in the plane.
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Origin of the plane.
+pub fn origin() -> Point {
+    Point { x: 0.0, y: 0.0 }
+}
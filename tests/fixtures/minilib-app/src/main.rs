@@ -0,0 +1,11 @@
+//! A minilib user with a typo and calls removed or deprecated in 0.2, for
+//! the `upgrade_advice` conformance case.
+
+use minilib_core::{Point, Pointt};
+
+fn main() {
+    let p = minilib_core::Point::new(3.0, 4.0);
+    let length = minilib_core::Point::magnitude(&p);
+    println!("{length}");
+    let _ = minilib_core::origin();
+}
@@ -0,0 +1,38 @@
+//! Core types for the minilib fixture.
+
+pub mod shapes;
+
+/// A point in the plane.
+///
+/// ```
+/// use minilib_core::Point;
+/// let origin = Point::new(0.0, 0.0);
+/// assert_eq!(origin.x, 0.0);
+/// ```
+pub struct Point {
+    /// Horizontal coordinate.
+    pub x: f64,
+    /// Vertical coordinate.
+    pub y: f64,
+}
+
+impl Point {
+    /// Create a point from its coordinates.
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Euclidean distance to another point.
+    pub fn distance(&self, other: &Point) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
+
+    /// Distance from the origin.
+    #[deprecated(since = "0.2.0", note = "use `distance` to `Point::new(0.0, 0.0)`")]
+    pub fn magnitude(&self) -> f64 {
+        self.distance(&Point::new(0.0, 0.0))
+    }
+}
+
+/// Tolerance used by approximate comparisons.
+pub const EPSILON: f64 = 1e-9;
@@ -0,0 +1,17 @@
+//! Shapes built from points.
+
+use crate::Point;
+
+/// A closed shape.
+pub enum Shape {
+    /// A circle around a center point.
+    Circle { center: Point, radius: f64 },
+    /// An axis-aligned square given by its corner and side length.
+    Square(Point, f64),
+}
+
+/// Types with a measurable area.
+pub trait Area {
+    /// The enclosed area.
+    fn area(&self) -> f64;
+}
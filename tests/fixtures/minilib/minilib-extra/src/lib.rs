@@ -0,0 +1,8 @@
+//! Optional helpers for the minilib fixture.
+
+use minilib_core::Point;
+
+/// Midpoint of two points.
+pub fn midpoint(a: &Point, b: &Point) -> Point {
+    Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0)
+}